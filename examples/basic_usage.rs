@@ -66,7 +66,7 @@ async fn main() {
 
     // Teste 3: Clear cache
     println!("🗑️ Testing cache clear...");
-    user_loader.clear();
+    user_loader.clear().await;
     let after_clear = user_loader.load(1).await;
     match after_clear {
         Ok(user) => println!("✅ After clear User 1: {}", user.name),
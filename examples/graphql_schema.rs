@@ -0,0 +1,91 @@
+// examples/graphql_schema.rs
+use async_graphql::{EmptyMutation, EmptySubscription, ErrorExtensions, Object, Schema};
+use async_graphql_dataloader::{
+    DataLoader, DataLoaderContext, DataLoaderError, DataLoaderScope, TryBatchLoad,
+};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+struct User {
+    id: i32,
+    name: String,
+}
+
+#[Object]
+impl User {
+    async fn id(&self) -> i32 {
+        self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+struct UserLoader;
+
+#[async_trait::async_trait]
+impl TryBatchLoad for UserLoader {
+    type Key = i32;
+    type Value = User;
+    type Error = DataLoaderError;
+
+    async fn try_load(&self, keys: &[i32]) -> Result<HashMap<i32, User>, DataLoaderError> {
+        println!("🚀 BATCH LOADING {} users: {:?}", keys.len(), keys);
+
+        // Ids negativos não existem e viram `KEY_NOT_FOUND`
+        Ok(keys
+            .iter()
+            .filter(|&&id| id > 0)
+            .map(|&id| {
+                let user = User {
+                    id,
+                    name: format!("User {}", id),
+                };
+                (id, user)
+            })
+            .collect())
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn user(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        id: i32,
+    ) -> async_graphql::Result<Option<User>> {
+        ctx.data_loader::<UserLoader>()?
+            .load(id)
+            .await
+            .map(Some)
+            .map_err(|err| err.extend())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Cada request usa um fork do loader com cache próprio e recebe em
+    // `extensions.dataloader` o resumo do que carregou
+    let user_loader = DataLoader::new(UserLoader);
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(user_loader.clone())
+        .extension(
+            DataLoaderScope::new()
+                .with_loader(&user_loader)
+                .with_activity_report(),
+        )
+        .finish();
+
+    // `a` e `b` saem do mesmo batch
+    let response = schema
+        .execute("{ a: user(id: 1) { name } b: user(id: 2) { name } }")
+        .await;
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+
+    // O erro chega ao cliente com `extensions.code = KEY_NOT_FOUND`
+    let response = schema.execute("{ user(id: -1) { name } }").await;
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+}
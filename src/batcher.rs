@@ -1,29 +1,59 @@
 // src/batcher.rs
-use std::{collections::HashMap, sync::Arc, time::Duration};
-use tokio::sync::{Mutex, oneshot, RwLock};
-use crate::loader::BatchLoad;
 use crate::error::DataLoaderError;
+use crate::loader::BatchLoad;
+use futures::StreamExt;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::{oneshot, Mutex, RwLock};
 
 type BatchResult<V> = oneshot::Sender<Result<V, DataLoaderError>>;
 type PendingBatch<K, V> = HashMap<K, Vec<BatchResult<V>>>;
+type Batch<K, V> = Vec<(K, Vec<BatchResult<V>>)>;
 
 pub struct Batcher<L: BatchLoad> {
     loader: Arc<L>,
-    pending: Mutex<PendingBatch<L::Key, L::Value>>,
+    pending: Arc<Mutex<PendingBatch<L::Key, L::Value>>>,
     metrics: Arc<Metrics>,
+    delay: Duration,
+    max_batch_size: usize,
+    max_concurrent_batches: usize,
+    batch_timeout: Option<Duration>,
 }
 
 #[derive(Clone, Debug)]
 pub struct Metrics {
     pub batches_dispatched: Arc<RwLock<u64>>,
     pub keys_processed: Arc<RwLock<u64>>,
+    pub loads_deduplicated: Arc<RwLock<u64>>,
+    pub timeouts: Arc<RwLock<u64>>,
+    pub cache_hits: Arc<RwLock<u64>>,
+    pub cache_misses: Arc<RwLock<u64>>,
+    pub load_time: Arc<RwLock<Duration>>,
+    // Métricas agregadas que também recebem tudo que é registrado aqui
+    parent: Option<Arc<Metrics>>,
 }
 
+type Counter = fn(&Metrics) -> &Arc<RwLock<u64>>;
+
 impl Metrics {
     pub fn new() -> Self {
         Self {
             batches_dispatched: Arc::new(RwLock::new(0)),
             keys_processed: Arc::new(RwLock::new(0)),
+            loads_deduplicated: Arc::new(RwLock::new(0)),
+            timeouts: Arc::new(RwLock::new(0)),
+            cache_hits: Arc::new(RwLock::new(0)),
+            cache_misses: Arc::new(RwLock::new(0)),
+            load_time: Arc::new(RwLock::new(Duration::ZERO)),
+            parent: None,
+        }
+    }
+
+    /// Métricas próprias que também acumulam em `parent`, como as de um
+    /// loader com escopo de request.
+    pub fn with_parent(parent: Arc<Metrics>) -> Self {
+        Self {
+            parent: Some(parent),
+            ..Self::new()
         }
     }
 
@@ -31,49 +61,134 @@ impl Metrics {
         BatchStats {
             batches_dispatched: *self.batches_dispatched.read().await,
             keys_processed: *self.keys_processed.read().await,
+            loads_deduplicated: *self.loads_deduplicated.read().await,
+            timeouts: *self.timeouts.read().await,
+            cache_hits: *self.cache_hits.read().await,
+            cache_misses: *self.cache_misses.read().await,
+            load_time: *self.load_time.read().await,
         }
     }
+
+    pub(crate) async fn increment(&self, counter: Counter, amount: u64) {
+        let mut metrics = Some(self);
+        while let Some(current) = metrics {
+            *counter(current).write().await += amount;
+            metrics = current.parent.as_deref();
+        }
+    }
+
+    pub(crate) async fn add_load_time(&self, elapsed: Duration) {
+        let mut metrics = Some(self);
+        while let Some(current) = metrics {
+            *current.load_time.write().await += elapsed;
+            metrics = current.parent.as_deref();
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct BatchStats {
     pub batches_dispatched: u64,
     pub keys_processed: u64,
+    /// Loads que reaproveitaram uma busca já em andamento para a mesma key.
+    pub loads_deduplicated: u64,
+    /// Timeouts de batch e de chamadas individuais a `load`.
+    pub timeouts: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// Tempo total gasto em `BatchLoad::load`, somando batches concorrentes.
+    pub load_time: Duration,
 }
 
-impl<L> Batcher<L> 
-where 
+impl<L> Batcher<L>
+where
     L: BatchLoad + 'static,
     L::Key: Clone + Eq + std::hash::Hash + std::fmt::Debug,
     L::Value: Clone,
-    L::Error: From<String> + std::fmt::Display,
 {
     pub fn new(loader: Arc<L>) -> Self {
         Self {
             loader,
-            pending: Mutex::new(HashMap::new()),
+            pending: Arc::new(Mutex::new(HashMap::new())),
             metrics: Arc::new(Metrics::new()),
+            delay: Duration::from_millis(10),
+            max_batch_size: 100,
+            max_concurrent_batches: 1,
+            batch_timeout: None,
         }
     }
 
+    /// Tempo que o batcher espera coletando keys antes de despachar um batch.
+    /// Com `Duration::ZERO` o batch é despachado assim que o tick atual do
+    /// runtime termina.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Número máximo de keys por chamada a `BatchLoad::load`. Janelas maiores
+    /// são divididas em vários batches.
+    pub fn with_max_batch_size(mut self, size: usize) -> Self {
+        self.max_batch_size = size.max(1);
+        self
+    }
+
+    /// Quantos batches de uma mesma janela podem executar ao mesmo tempo.
+    pub fn with_max_concurrent_batches(mut self, limit: usize) -> Self {
+        self.max_concurrent_batches = limit.max(1);
+        self
+    }
+
+    /// Prazo máximo de uma chamada a `BatchLoad::load`. Ao estourar, o load é
+    /// cancelado e todos do batch recebem `DataLoaderError::Timeout`.
+    pub fn with_batch_timeout(mut self, timeout: Duration) -> Self {
+        self.batch_timeout = Some(timeout);
+        self
+    }
+
+    pub fn loader(&self) -> &Arc<L> {
+        &self.loader
+    }
+
     pub fn metrics(&self) -> Arc<Metrics> {
         Arc::clone(&self.metrics)
     }
 
+    /// Batcher com fila e métricas próprias sobre o mesmo loader. As métricas
+    /// do fork também acumulam nas deste batcher.
+    pub fn fork(&self) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            metrics: Arc::new(Metrics::with_parent(self.metrics())),
+            ..self.clone()
+        }
+    }
+
     pub async fn schedule(&self, key: L::Key) -> Result<L::Value, DataLoaderError> {
         let (tx, rx) = oneshot::channel();
-        
+
+        // A primeira key de uma janela vazia dispara o dispatcher da janela
         let should_dispatch = {
             let mut pending = self.pending.lock().await;
-            let entry = pending.entry(key.clone()).or_insert_with(Vec::new);
-            entry.push(tx);
-            
-            // Dispara imediatamente para batches pequenos (REMOVEMOS O DELAY)
-            true // SEMPRE dispara imediatamente para teste
+            let opens_window = pending.is_empty();
+            pending.entry(key).or_default().push(tx);
+            opens_window
         };
 
         if should_dispatch {
-            self.dispatch_batch_for_key(key.clone()).await;
+            // O batch roda numa task própria, assim cancelar o chamador que
+            // abriu a janela não afeta quem aguarda as outras keys
+            let batcher = self.clone();
+            tokio::spawn(async move {
+                batcher.wait_window().await;
+                batcher.dispatch_pending().await;
+            });
         }
 
         match rx.await {
@@ -82,35 +197,82 @@ where
         }
     }
 
-    async fn dispatch_batch_for_key(&self, key: L::Key) {
-        let batch = {
+    async fn wait_window(&self) {
+        if self.delay.is_zero() {
+            tokio::task::yield_now().await;
+        } else {
+            tokio::time::sleep(self.delay).await;
+        }
+    }
+
+    async fn dispatch_pending(&self) {
+        let mut batch: Batch<L::Key, L::Value> = {
             let mut pending = self.pending.lock().await;
-            pending.remove(&key).map(|senders| {
-                vec![(key, senders)]
-            }).unwrap_or_else(Vec::new)
+            std::mem::take(&mut *pending).into_iter().collect()
         };
 
-        if !batch.is_empty() {
-            self.process_batch(batch).await;
+        if batch.is_empty() {
+            return;
+        }
+
+        // Divide a janela em chunks de no máximo `max_batch_size` keys
+        let mut chunks = Vec::with_capacity(batch.len().div_ceil(self.max_batch_size));
+        while batch.len() > self.max_batch_size {
+            let rest = batch.split_off(self.max_batch_size);
+            chunks.push(batch);
+            batch = rest;
         }
+        chunks.push(batch);
+
+        futures::stream::iter(chunks)
+            .for_each_concurrent(self.max_concurrent_batches, |chunk| {
+                self.process_batch(chunk)
+            })
+            .await;
     }
 
-    async fn process_batch(&self, batch: Vec<(L::Key, Vec<BatchResult<L::Value>>)>) {
+    async fn process_batch(&self, batch: Batch<L::Key, L::Value>) {
         let keys: Vec<L::Key> = batch.iter().map(|(key, _)| key.clone()).collect();
-        
+
         if keys.is_empty() {
             return;
         }
 
-        *self.metrics.keys_processed.write().await += keys.len() as u64;
-        *self.metrics.batches_dispatched.write().await += 1;
+        self.metrics
+            .increment(|m| &m.keys_processed, keys.len() as u64)
+            .await;
+        self.metrics.increment(|m| &m.batches_dispatched, 1).await;
 
-        let results = self.loader.load(&keys).await;
+        let started = std::time::Instant::now();
+        let load = self.loader.load_batch(&keys);
+        let outcome = match self.batch_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, load).await,
+            None => Ok(load.await),
+        };
+        self.metrics.add_load_time(started.elapsed()).await;
+
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                // O load travado é descartado junto com o future
+                self.metrics.increment(|m| &m.timeouts, 1).await;
+                Self::fail_batch(batch, DataLoaderError::Timeout);
+                return;
+            }
+        };
+
+        let mut results = match outcome {
+            Ok(results) => results,
+            Err(err) => {
+                Self::fail_batch(batch, DataLoaderError::batch_error(err));
+                return;
+            }
+        };
 
         for (key, senders) in batch {
-            let result = match results.get(&key) {
-                Some(Ok(value)) => Ok(value.clone()),
-                Some(Err(err)) => Err(DataLoaderError::BatchError(format!("{}", err))),
+            let result = match results.remove(&key) {
+                Some(Ok(value)) => Ok(value),
+                Some(Err(err)) => Err(DataLoaderError::batch_error(err)),
                 None => Err(DataLoaderError::KeyNotFound),
             };
 
@@ -119,8 +281,17 @@ where
             }
         }
     }
+
+    // Entrega o mesmo erro a todos que aguardam keys do batch
+    fn fail_batch(batch: Batch<L::Key, L::Value>, error: DataLoaderError) {
+        for sender in batch.into_iter().flat_map(|(_, senders)| senders) {
+            let _ = sender.send(Err(error.clone()));
+        }
+    }
 }
 
+// Clones compartilham a fila pendente, então keys agendadas por qualquer
+// handle entram na mesma janela.
 impl<L> Clone for Batcher<L>
 where
     L: BatchLoad,
@@ -128,8 +299,12 @@ where
     fn clone(&self) -> Self {
         Self {
             loader: Arc::clone(&self.loader),
-            pending: Mutex::new(HashMap::new()),
+            pending: Arc::clone(&self.pending),
             metrics: Arc::clone(&self.metrics),
+            delay: self.delay,
+            max_batch_size: self.max_batch_size,
+            max_concurrent_batches: self.max_concurrent_batches,
+            batch_timeout: self.batch_timeout,
         }
     }
-}
\ No newline at end of file
+}
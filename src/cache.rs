@@ -1,12 +1,254 @@
 // src/cache.rs
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     time::{Duration, Instant},
 };
 
+/// Política usada para escolher quem sai quando o cache atinge a capacidade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Remove a entrada acessada há mais tempo.
+    Lru,
+    /// Remove a entrada com menos acessos; empates saem pela mais antiga.
+    Lfu,
+}
+
+/// Backend de cache usado pelo `DataLoader`. `Cache` é a implementação
+/// padrão; outras (moka, Redis, um cache no-op) podem ser plugadas com
+/// `DataLoader::with_cache`.
+#[async_trait]
+pub trait CacheStore<K, V>: Send + Sync
+where
+    K: Send + Sync + 'static,
+    V: Send + 'static,
+{
+    async fn get(&self, key: &K) -> Option<V>;
+
+    async fn set(&self, key: K, value: V);
+
+    /// Backends sem suporte a TTL por entrada podem manter o padrão, que
+    /// ignora o `ttl`.
+    async fn set_with_ttl(&self, key: K, value: V, _ttl: Duration) {
+        self.set(key, value).await;
+    }
+
+    /// Como `get`, mas informando se a entrada já passou do soft TTL. O padrão
+    /// trata toda entrada como fresca.
+    async fn lookup(&self, key: &K) -> Option<CacheLookup<V>> {
+        self.get(key).await.map(CacheLookup::Fresh)
+    }
+
+    /// Grava com as opções da entrada. O padrão aplica apenas o TTL.
+    async fn set_with_options(&self, key: K, value: V, options: EntryOptions) {
+        match options.ttl {
+            Some(ttl) => self.set_with_ttl(key, value, ttl).await,
+            None => self.set(key, value).await,
+        }
+    }
+
+    async fn remove(&self, key: &K);
+
+    /// Remove as entradas para as quais `predicate` retorna `true` e devolve
+    /// suas keys. Não há padrão: limpar o store inteiro apagaria entradas de
+    /// todos os loaders que o compartilham.
+    async fn remove_where(
+        &self,
+        predicate: &(dyn for<'k, 'v> Fn(&'k K, &'v V) -> bool + Send + Sync),
+    ) -> Vec<K>;
+
+    /// Remove todas as entradas marcadas com `tag` e devolve suas keys.
+    /// Backends sem suporte a tags devem devolver uma lista vazia.
+    async fn invalidate_tag(&self, tag: &str) -> Vec<K>;
+
+    async fn clear(&self);
+
+    /// Remove entradas com TTL vencido e devolve quantas saíram. Backends
+    /// que expiram sozinhos podem manter o padrão.
+    async fn sweep_expired(&self) -> usize {
+        0
+    }
+
+    /// Entradas atuais para `DataLoader::export_json`. Backends que não
+    /// conseguem listar suas entradas podem manter o padrão, vazio.
+    async fn export(&self) -> Vec<SnapshotEntry<K, V>> {
+        Vec::new()
+    }
+
+    async fn import(&self, entries: Vec<SnapshotEntry<K, V>>) {
+        for entry in entries {
+            let options = entry.options();
+            self.set_with_options(entry.key, entry.value, options).await;
+        }
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+}
+
+/// Entrada de um snapshot do cache. Os TTLs guardam o tempo de vida
+/// restante no momento da exportação, em milissegundos.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry<K, V> {
+    pub key: K,
+    pub value: V,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_after_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl<K, V> SnapshotEntry<K, V> {
+    pub fn options(&self) -> EntryOptions {
+        EntryOptions {
+            ttl: self.ttl_ms.map(Duration::from_millis),
+            stale_after: self.stale_after_ms.map(Duration::from_millis),
+            tags: self.tags.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshot<K, V> {
+    pub entries: Vec<SnapshotEntry<K, V>>,
+}
+
+/// Opções de uma entrada do cache.
+#[derive(Debug, Clone, Default)]
+pub struct EntryOptions {
+    /// Hard TTL: depois dele a entrada deixa de existir.
+    pub ttl: Option<Duration>,
+    /// Soft TTL: depois dele a entrada ainda é servida, mas como `Stale`.
+    pub stale_after: Option<Duration>,
+    /// Tags como `org:42` ou `table:users`, usadas por `invalidate_tag`.
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CacheLookup<V> {
+    Fresh(V),
+    /// Passou do soft TTL; deve ser servida enquanto é revalidada.
+    Stale(V),
+}
+
+impl<V> CacheLookup<V> {
+    pub fn into_value(self) -> V {
+        match self {
+            CacheLookup::Fresh(value) | CacheLookup::Stale(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub evictions: u64,
+    /// Entradas vencidas removidas por `sweep_expired`.
+    pub swept: u64,
+}
+
+struct Entry<V> {
+    value: V,
+    inserted_at: Instant,
+    // TTL próprio da entrada; quando ausente vale o TTL do cache
+    ttl: Option<Duration>,
+    stale_after: Option<Duration>,
+    tags: Vec<String>,
+}
+
+// Ordem de despejo: (acessos, tick). Na LRU os acessos ficam sempre em zero.
+type Rank = (u64, u64);
+
+struct EvictionIndex<K> {
+    policy: EvictionPolicy,
+    capacity: usize,
+    order: BTreeMap<Rank, K>,
+    ranks: HashMap<K, Rank>,
+    tick: u64,
+}
+
+impl<K> EvictionIndex<K>
+where
+    K: Eq + Hash + Clone,
+{
+    fn new(capacity: usize, policy: EvictionPolicy) -> Self {
+        Self {
+            policy,
+            capacity: capacity.max(1),
+            order: BTreeMap::new(),
+            ranks: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(rank) = self.ranks.get(key).copied() {
+            self.order.remove(&rank);
+            self.rank(key.clone(), rank.0 + 1);
+        }
+    }
+
+    // Devolve as keys despejadas para abrir espaço. A key nova nunca é a
+    // escolhida: na LFU ela começaria com menos acessos que todas as outras
+    fn insert(&mut self, key: K) -> Vec<K> {
+        if let Some(rank) = self.ranks.get(&key).copied() {
+            self.order.remove(&rank);
+            self.rank(key, rank.0 + 1);
+            return Vec::new();
+        }
+
+        let mut victims = Vec::new();
+        while self.ranks.len() >= self.capacity {
+            let Some((_, victim)) = self.order.pop_first() else {
+                break;
+            };
+            self.ranks.remove(&victim);
+            victims.push(victim);
+        }
+
+        self.rank(key, 1);
+        victims
+    }
+
+    fn rank(&mut self, key: K, hits: u64) {
+        self.tick += 1;
+        let rank = match self.policy {
+            EvictionPolicy::Lru => (0, self.tick),
+            EvictionPolicy::Lfu => (hits, self.tick),
+        };
+        self.order.insert(rank, key.clone());
+        self.ranks.insert(key, rank);
+    }
+
+    fn remove(&mut self, key: &K) {
+        if let Some(rank) = self.ranks.remove(key) {
+            self.order.remove(&rank);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.order.clear();
+        self.ranks.clear();
+    }
+}
+
 pub struct Cache<K, V> {
-    store: dashmap::DashMap<K, (V, Instant)>,
+    store: dashmap::DashMap<K, Entry<V>>,
+    tags: dashmap::DashMap<String, HashSet<K>>,
     ttl: Option<Duration>,
+    // Presente apenas em caches com capacidade máxima
+    eviction: Option<Mutex<EvictionIndex<K>>>,
+    evictions: AtomicU64,
+    swept: AtomicU64,
 }
 
 impl<K, V> Cache<K, V>
@@ -17,7 +259,11 @@ where
     pub fn new() -> Self {
         Self {
             store: dashmap::DashMap::new(),
+            tags: dashmap::DashMap::new(),
             ttl: None,
+            eviction: None,
+            evictions: AtomicU64::new(0),
+            swept: AtomicU64::new(0),
         }
     }
 
@@ -26,24 +272,567 @@ where
         self
     }
 
+    pub(crate) fn default_ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// Limita o cache a `capacity` entradas, despejando conforme `policy`.
+    pub fn with_capacity(mut self, capacity: usize, policy: EvictionPolicy) -> Self {
+        self.eviction = Some(Mutex::new(EvictionIndex::new(capacity, policy)));
+        self
+    }
+
     pub fn get(&self, key: &K) -> Option<V> {
+        self.lookup(key).map(CacheLookup::into_value)
+    }
+
+    pub fn lookup(&self, key: &K) -> Option<CacheLookup<V>> {
         let entry = self.store.get(key)?;
+        let age = entry.inserted_at.elapsed();
 
-        if let Some(ttl) = self.ttl {
-            if entry.1.elapsed() > ttl {
-                self.store.remove(key);
-                return None;
-            }
+        if self.is_expired(&entry) {
+            drop(entry);
+            self.remove(key);
+            return None;
         }
 
-        Some(entry.0.clone())
+        let value = entry.value.clone();
+        let stale = entry
+            .stale_after
+            .is_some_and(|stale_after| age > stale_after);
+        drop(entry);
+
+        if let Some(eviction) = &self.eviction {
+            eviction.lock().unwrap().touch(key);
+        }
+
+        Some(if stale {
+            CacheLookup::Stale(value)
+        } else {
+            CacheLookup::Fresh(value)
+        })
     }
 
     pub fn set(&self, key: K, value: V) {
-        self.store.insert(key, (value, Instant::now()));
+        self.insert(key, value, EntryOptions::default());
+    }
+
+    /// Insere uma entrada com TTL próprio, ignorando o TTL do cache.
+    pub fn set_with_ttl(&self, key: K, value: V, ttl: Duration) {
+        let options = EntryOptions {
+            ttl: Some(ttl),
+            ..EntryOptions::default()
+        };
+        self.insert(key, value, options);
+    }
+
+    pub fn set_with_options(&self, key: K, value: V, options: EntryOptions) {
+        self.insert(key, value, options);
+    }
+
+    pub fn remove(&self, key: &K) {
+        match &self.eviction {
+            Some(eviction) => {
+                let mut eviction = eviction.lock().unwrap();
+                self.remove_entry(key);
+                eviction.remove(key);
+            }
+            None => self.remove_entry(key),
+        }
+    }
+
+    /// Remove todas as entradas marcadas com `tag`.
+    pub fn invalidate_tag(&self, tag: &str) -> Vec<K> {
+        let keys: Vec<K> = match self.tags.remove(tag) {
+            Some((_, keys)) => keys.into_iter().collect(),
+            None => return Vec::new(),
+        };
+
+        for key in &keys {
+            self.remove(key);
+        }
+        keys
+    }
+
+    pub fn remove_where<F>(&self, predicate: F) -> Vec<K>
+    where
+        F: Fn(&K, &V) -> bool,
+    {
+        let keys: Vec<K> = self
+            .store
+            .iter()
+            .filter(|entry| predicate(entry.key(), &entry.value().value))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for key in &keys {
+            self.remove(key);
+        }
+        keys
     }
 
     pub fn clear(&self) {
-        self.store.clear();
+        match &self.eviction {
+            Some(eviction) => {
+                let mut eviction = eviction.lock().unwrap();
+                self.store.clear();
+                self.tags.clear();
+                eviction.clear();
+            }
+            None => {
+                self.store.clear();
+                self.tags.clear();
+            }
+        }
+    }
+
+    /// Remove todas as entradas com TTL vencido, inclusive as que nunca mais
+    /// seriam lidas.
+    pub fn sweep_expired(&self) -> usize {
+        let expired: Vec<K> = self
+            .store
+            .iter()
+            .filter(|entry| self.is_expired(entry.value()))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let mut swept = 0;
+        for key in &expired {
+            // Confere de novo: a key pode ter sido regravada nesse meio tempo
+            let mut eviction = self
+                .eviction
+                .as_ref()
+                .map(|eviction| eviction.lock().unwrap());
+            if let Some((key, entry)) = self.store.remove_if(key, |_, entry| self.is_expired(entry))
+            {
+                self.untag(&key, &entry.tags);
+                if let Some(eviction) = eviction.as_mut() {
+                    eviction.remove(&key);
+                }
+                swept += 1;
+            }
+        }
+
+        self.swept.fetch_add(swept as u64, Ordering::Relaxed);
+        swept
+    }
+
+    /// Copia as entradas vivas, convertendo TTLs em tempo de vida restante.
+    pub fn snapshot(&self) -> Vec<SnapshotEntry<K, V>> {
+        self.store
+            .iter()
+            .filter(|entry| !self.is_expired(entry.value()))
+            .map(|entry| {
+                let (key, entry) = entry.pair();
+                let age = entry.inserted_at.elapsed();
+                let remaining = |limit: Duration| limit.saturating_sub(age).as_millis() as u64;
+
+                SnapshotEntry {
+                    key: key.clone(),
+                    value: entry.value.clone(),
+                    ttl_ms: entry.ttl.or(self.ttl).map(remaining),
+                    stale_after_ms: entry.stale_after.map(remaining),
+                    tags: entry.tags.clone(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn restore(&self, entries: Vec<SnapshotEntry<K, V>>) {
+        for entry in entries {
+            let options = entry.options();
+            self.insert(entry.key, entry.value, options);
+        }
+    }
+
+    pub fn export_json(&self) -> serde_json::Result<String>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        serde_json::to_string(&CacheSnapshot {
+            entries: self.snapshot(),
+        })
+    }
+
+    /// Restaura um snapshot gerado por `export_json`, devolvendo quantas
+    /// entradas foram importadas.
+    pub fn import_json(&self, json: &str) -> serde_json::Result<usize>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let snapshot: CacheSnapshot<K, V> = serde_json::from_str(json)?;
+        let count = snapshot.entries.len();
+        self.restore(snapshot.entries);
+        Ok(count)
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.len(),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            swept: self.swept.load(Ordering::Relaxed),
+        }
+    }
+
+    fn is_expired(&self, entry: &Entry<V>) -> bool {
+        entry
+            .ttl
+            .or(self.ttl)
+            .is_some_and(|ttl| entry.inserted_at.elapsed() > ttl)
+    }
+
+    fn insert(&self, key: K, value: V, options: EntryOptions) {
+        let entry = Entry {
+            value,
+            inserted_at: Instant::now(),
+            ttl: options.ttl,
+            stale_after: options.stale_after,
+            tags: options.tags,
+        };
+
+        let Some(eviction) = &self.eviction else {
+            self.insert_entry(key, entry);
+            return;
+        };
+
+        // Com capacidade limitada, store e índice mudam juntos sob o mesmo lock
+        let mut eviction = eviction.lock().unwrap();
+        let victims = eviction.insert(key.clone());
+        self.insert_entry(key, entry);
+
+        for victim in &victims {
+            self.remove_entry(victim);
+        }
+        self.evictions
+            .fetch_add(victims.len() as u64, Ordering::Relaxed);
+    }
+
+    fn insert_entry(&self, key: K, entry: Entry<V>) {
+        for tag in &entry.tags {
+            self.tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+
+        if let Some(previous) = self.store.insert(key.clone(), entry) {
+            self.untag(&key, &previous.tags);
+        }
+    }
+
+    fn remove_entry(&self, key: &K) {
+        if let Some((key, entry)) = self.store.remove(key) {
+            self.untag(&key, &entry.tags);
+        }
+    }
+
+    fn untag(&self, key: &K, tags: &[String]) {
+        for tag in tags {
+            // Mantém a key se a entrada atual continua com a mesma tag
+            let still_tagged = self
+                .store
+                .get(key)
+                .is_some_and(|entry| entry.tags.contains(tag));
+            if still_tagged {
+                continue;
+            }
+
+            if let Some(mut keys) = self.tags.get_mut(tag) {
+                keys.remove(key);
+            }
+            self.tags.remove_if(tag, |_, keys| keys.is_empty());
+        }
+    }
+}
+
+impl<K, V> Default for Cache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<K, V> CacheStore<K, V> for Cache<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    async fn get(&self, key: &K) -> Option<V> {
+        Cache::get(self, key)
+    }
+
+    async fn set(&self, key: K, value: V) {
+        Cache::set(self, key, value);
+    }
+
+    async fn set_with_ttl(&self, key: K, value: V, ttl: Duration) {
+        Cache::set_with_ttl(self, key, value, ttl);
+    }
+
+    async fn lookup(&self, key: &K) -> Option<CacheLookup<V>> {
+        Cache::lookup(self, key)
+    }
+
+    async fn set_with_options(&self, key: K, value: V, options: EntryOptions) {
+        Cache::set_with_options(self, key, value, options);
+    }
+
+    async fn remove(&self, key: &K) {
+        Cache::remove(self, key);
+    }
+
+    async fn remove_where(
+        &self,
+        predicate: &(dyn for<'k, 'v> Fn(&'k K, &'v V) -> bool + Send + Sync),
+    ) -> Vec<K> {
+        Cache::remove_where(self, predicate)
+    }
+
+    async fn invalidate_tag(&self, tag: &str) -> Vec<K> {
+        Cache::invalidate_tag(self, tag)
+    }
+
+    async fn clear(&self) {
+        Cache::clear(self);
+    }
+
+    async fn sweep_expired(&self) -> usize {
+        Cache::sweep_expired(self)
+    }
+
+    async fn export(&self) -> Vec<SnapshotEntry<K, V>> {
+        Cache::snapshot(self)
+    }
+
+    async fn import(&self, entries: Vec<SnapshotEntry<K, V>>) {
+        Cache::restore(self, entries);
+    }
+
+    fn stats(&self) -> CacheStats {
+        Cache::stats(self)
+    }
+}
+
+// Permite compartilhar um mesmo store entre vários loaders
+#[async_trait]
+impl<K, V, S> CacheStore<K, V> for Arc<S>
+where
+    K: Send + Sync + 'static,
+    V: Send + 'static,
+    S: CacheStore<K, V> + ?Sized,
+{
+    async fn get(&self, key: &K) -> Option<V> {
+        (**self).get(key).await
+    }
+
+    async fn set(&self, key: K, value: V) {
+        (**self).set(key, value).await;
+    }
+
+    async fn set_with_ttl(&self, key: K, value: V, ttl: Duration) {
+        (**self).set_with_ttl(key, value, ttl).await;
+    }
+
+    async fn lookup(&self, key: &K) -> Option<CacheLookup<V>> {
+        (**self).lookup(key).await
+    }
+
+    async fn set_with_options(&self, key: K, value: V, options: EntryOptions) {
+        (**self).set_with_options(key, value, options).await;
+    }
+
+    async fn remove(&self, key: &K) {
+        (**self).remove(key).await;
+    }
+
+    async fn remove_where(
+        &self,
+        predicate: &(dyn for<'k, 'v> Fn(&'k K, &'v V) -> bool + Send + Sync),
+    ) -> Vec<K> {
+        (**self).remove_where(predicate).await
+    }
+
+    async fn invalidate_tag(&self, tag: &str) -> Vec<K> {
+        (**self).invalidate_tag(tag).await
+    }
+
+    async fn clear(&self) {
+        (**self).clear().await;
+    }
+
+    async fn sweep_expired(&self) -> usize {
+        (**self).sweep_expired().await
+    }
+
+    async fn export(&self) -> Vec<SnapshotEntry<K, V>> {
+        (**self).export().await
+    }
+
+    async fn import(&self, entries: Vec<SnapshotEntry<K, V>>) {
+        (**self).import(entries).await;
+    }
+
+    fn stats(&self) -> CacheStats {
+        (**self).stats()
+    }
+}
+
+/// Store que não guarda nada, útil em caminhos de mutation onde toda leitura
+/// deve ir à fonte.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoCache;
+
+#[async_trait]
+impl<K, V> CacheStore<K, V> for NoCache
+where
+    K: Send + Sync + 'static,
+    V: Send + 'static,
+{
+    async fn get(&self, _key: &K) -> Option<V> {
+        None
+    }
+
+    async fn set(&self, _key: K, _value: V) {}
+
+    async fn remove(&self, _key: &K) {}
+
+    async fn remove_where(
+        &self,
+        _predicate: &(dyn for<'k, 'v> Fn(&'k K, &'v V) -> bool + Send + Sync),
+    ) -> Vec<K> {
+        Vec::new()
+    }
+
+    async fn invalidate_tag(&self, _tag: &str) -> Vec<K> {
+        Vec::new()
+    }
+
+    async fn clear(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounded(policy: EvictionPolicy) -> Cache<u32, u32> {
+        Cache::new().with_capacity(2, policy)
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let cache = bounded(EvictionPolicy::Lru);
+        cache.set(1, 10);
+        cache.set(2, 20);
+        cache.get(&1);
+
+        cache.set(3, 30);
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some(10));
+        assert_eq!(cache.get(&3), Some(30));
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn lfu_evicts_least_frequently_used() {
+        let cache = bounded(EvictionPolicy::Lfu);
+        cache.set(1, 10);
+        cache.set(2, 20);
+        cache.get(&1);
+        cache.get(&1);
+        cache.get(&2);
+
+        // 2 foi acessada por último, mas tem menos acessos que 1
+        cache.set(3, 30);
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some(10));
+        assert_eq!(cache.get(&3), Some(30));
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn lfu_ties_evict_oldest() {
+        let cache = bounded(EvictionPolicy::Lfu);
+        cache.set(1, 10);
+        cache.set(2, 20);
+
+        cache.set(3, 30);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some(20));
+        assert_eq!(cache.get(&3), Some(30));
+    }
+
+    #[test]
+    fn overwrite_and_remove_do_not_count_as_evictions() {
+        let cache = bounded(EvictionPolicy::Lru);
+        cache.set(1, 10);
+        cache.set(2, 20);
+        cache.set(2, 21);
+        cache.remove(&1);
+        cache.set(3, 30);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&2), Some(21));
+        assert_eq!(cache.stats().evictions, 0);
+    }
+
+    #[test]
+    fn eviction_drops_entry_from_tag_index() {
+        let cache = bounded(EvictionPolicy::Lru);
+        let tagged = EntryOptions {
+            tags: vec!["org:1".to_string()],
+            ..EntryOptions::default()
+        };
+        cache.set_with_options(1, 10, tagged.clone());
+        cache.set_with_options(2, 20, tagged);
+        cache.set(3, 30);
+
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.invalidate_tag("org:1"), vec![2]);
+        assert!(cache.tags.is_empty());
+        assert_eq!(cache.get(&3), Some(30));
+    }
+
+    #[test]
+    fn json_round_trip_keeps_remaining_ttl_and_tags() {
+        let cache: Cache<u32, u32> = Cache::new();
+        let options = EntryOptions {
+            ttl: Some(Duration::from_millis(100)),
+            tags: vec!["org:1".to_string()],
+            ..EntryOptions::default()
+        };
+        cache.set_with_options(1, 10, options);
+        cache.set(2, 20);
+        std::thread::sleep(Duration::from_millis(40));
+
+        let restored: Cache<u32, u32> = Cache::new();
+        assert_eq!(
+            restored.import_json(&cache.export_json().unwrap()).unwrap(),
+            2
+        );
+
+        let mut entries = restored.snapshot();
+        entries.sort_by_key(|entry| entry.key);
+        assert!(entries[0].ttl_ms.is_some_and(|ttl| ttl <= 60));
+        assert_eq!(entries[0].tags, vec!["org:1".to_string()]);
+        assert_eq!(entries[1].ttl_ms, None);
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(restored.get(&1), None);
+        assert_eq!(restored.get(&2), Some(20));
+        assert_eq!(restored.invalidate_tag("org:1"), Vec::<u32>::new());
     }
 }
@@ -1,31 +1,77 @@
 // src/error.rs
 use std::fmt;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub enum DataLoaderError {
     ChannelClosed,
-    BatchError(String),
+    /// Erro original retornado pelo `BatchLoad`, compartilhado entre todos os
+    /// que aguardam a mesma key.
+    BatchError(Arc<dyn std::error::Error + Send + Sync>),
     KeyNotFound,
     Timeout,
 }
 
+impl DataLoaderError {
+    pub fn batch_error<E>(err: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        // Um `DataLoaderError` já pronto (ex.: vindo de `TryBatchLoad`) não é
+        // embrulhado de novo
+        match err.into().downcast::<DataLoaderError>() {
+            Ok(err) => *err,
+            Err(err) => DataLoaderError::BatchError(Arc::from(err)),
+        }
+    }
+
+    /// Identificador estável da variante, usado por exemplo como `code` nas
+    /// extensions de erros GraphQL.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DataLoaderError::ChannelClosed => "CHANNEL_CLOSED",
+            DataLoaderError::BatchError(_) => "BATCH_ERROR",
+            DataLoaderError::KeyNotFound => "KEY_NOT_FOUND",
+            DataLoaderError::Timeout => "TIMEOUT",
+        }
+    }
+
+    /// Acessa o erro original do loader, por exemplo um `sqlx::Error`.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: std::error::Error + 'static,
+    {
+        match self {
+            DataLoaderError::BatchError(err) => err.downcast_ref::<E>(),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for DataLoaderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DataLoaderError::ChannelClosed => {
                 write!(f, "Channel closed while waiting for batch result")
             }
-            DataLoaderError::BatchError(msg) => write!(f, "Batch load error: {}", msg),
+            DataLoaderError::BatchError(err) => write!(f, "Batch load error: {}", err),
             DataLoaderError::KeyNotFound => write!(f, "Key not found in batch results"),
             DataLoaderError::Timeout => write!(f, "Timeout waiting for batch"),
         }
     }
 }
 
-impl std::error::Error for DataLoaderError {}
+impl std::error::Error for DataLoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DataLoaderError::BatchError(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl From<String> for DataLoaderError {
     fn from(err: String) -> Self {
-        DataLoaderError::BatchError(err)
+        DataLoaderError::batch_error(err)
     }
 }
@@ -0,0 +1,319 @@
+// src/integrations/graphql.rs
+use crate::batcher::Metrics;
+use crate::error::DataLoaderError;
+use crate::loader::{BatchLoad, DataLoader};
+use crate::registry::LoaderRegistry;
+use async_graphql::dataloader::Loader;
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest, NextRequest,
+};
+use async_graphql::{
+    Context, Error, ErrorExtensions, Request, Response, Result, ServerResult, Value,
+};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+/// Acesso aos loaders registrados no schema com
+/// `Schema::build(...).data(DataLoader::new(UserLoader))` ou dentro de um
+/// `LoaderRegistry` adicionado como data.
+pub trait DataLoaderContext {
+    /// Retorna o `DataLoader<L>` registrado, ou um erro GraphQL caso ele não
+    /// tenha sido adicionado ao schema.
+    fn data_loader<L>(&self) -> Result<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static;
+
+    fn data_loader_opt<L>(&self) -> Option<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static;
+}
+
+impl DataLoaderContext for Context<'_> {
+    fn data_loader<L>(&self) -> Result<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static,
+    {
+        self.data_loader_opt::<L>().ok_or_else(|| {
+            Error::new(format!(
+                "DataLoader<{}> is not registered",
+                std::any::type_name::<L>()
+            ))
+        })
+    }
+
+    fn data_loader_opt<L>(&self) -> Option<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static,
+    {
+        self.data_opt::<DataLoader<L>>()
+            .or_else(|| self.data_opt::<LoaderRegistry>()?.get::<L>())
+    }
+}
+
+/// Inclui `DataLoaderError::kind()` (ex.: `KEY_NOT_FOUND`) como `code` nas
+/// extensions do erro.
+///
+/// `?` num resolver não passa por aqui: o async-graphql converte o erro pelo
+/// `Display` e o `code` se perde. Para mantê-lo, converta com
+/// `.map_err(|err| err.extend())`.
+impl ErrorExtensions for DataLoaderError {
+    fn extend(&self) -> Error {
+        Error::new_with_source(self.clone()).extend_with(|_, extensions| {
+            extensions.set("code", self.kind());
+        })
+    }
+}
+
+/// Usa um `async_graphql::dataloader::Loader<K>` existente como `BatchLoad`,
+/// para migrar loaders aos poucos. O `Err` do loader falha o batch inteiro.
+pub struct AsBatchLoad<T, K> {
+    inner: T,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<T, K> AsBatchLoad<T, K> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            _key: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T, K> BatchLoad for AsBatchLoad<T, K>
+where
+    T: Loader<K>,
+    T::Error: Sync + Into<Box<dyn std::error::Error + Send + Sync>>,
+    K: Send + Sync + Clone + Eq + Hash + std::fmt::Debug + 'static,
+{
+    type Key = K;
+    type Value = T::Value;
+    type Error = T::Error;
+
+    async fn load(&self, keys: &[K]) -> HashMap<K, Result<T::Value, T::Error>> {
+        match self.inner.load(keys).await {
+            Ok(values) => values
+                .into_iter()
+                .map(|(key, value)| (key, Ok(value)))
+                .collect(),
+            Err(err) => keys
+                .iter()
+                .map(|key| (key.clone(), Err(err.clone())))
+                .collect(),
+        }
+    }
+
+    async fn load_batch(
+        &self,
+        keys: &[K],
+    ) -> Result<HashMap<K, Result<T::Value, T::Error>>, T::Error> {
+        let values = self.inner.load(keys).await?;
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| (key, Ok(value)))
+            .collect())
+    }
+}
+
+/// Expõe um `BatchLoad` como `async_graphql::dataloader::Loader`, para rodar
+/// o mesmo loader no dataloader do async-graphql. Como o `Loader` não tem
+/// erro por key, o primeiro erro por key falha o batch inteiro.
+pub struct AsGraphqlLoader<L> {
+    inner: Arc<L>,
+}
+
+impl<L> AsGraphqlLoader<L> {
+    pub fn new(loader: L) -> Self {
+        Self::from_arc(Arc::new(loader))
+    }
+
+    /// Compartilha o loader de um `DataLoader` existente (ver
+    /// `DataLoader::loader`).
+    pub fn from_arc(loader: Arc<L>) -> Self {
+        Self { inner: loader }
+    }
+
+    pub fn inner(&self) -> &Arc<L> {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<L> Loader<L::Key> for AsGraphqlLoader<L>
+where
+    L: BatchLoad + 'static,
+{
+    type Value = L::Value;
+    type Error = DataLoaderError;
+
+    async fn load(&self, keys: &[L::Key]) -> Result<HashMap<L::Key, L::Value>, DataLoaderError> {
+        let results = self
+            .inner
+            .load_batch(keys)
+            .await
+            .map_err(DataLoaderError::batch_error)?;
+
+        results
+            .into_iter()
+            .map(|(key, result)| {
+                result
+                    .map(|value| (key, value))
+                    .map_err(DataLoaderError::batch_error)
+            })
+            .collect()
+    }
+}
+
+/// Extension que isola o cache dos loaders por request. Para cada request,
+/// os loaders registrados são trocados por um `DataLoader::fork`, que
+/// compartilha o `Arc<L>` e as métricas mas começa com o cache vazio; o cache
+/// é descartado quando a request termina. `ctx.data_loader::<L>()` continua
+/// funcionando sem mudanças nos resolvers.
+#[derive(Clone, Default)]
+pub struct DataLoaderScope {
+    loaders: Vec<Arc<dyn ScopedLoader>>,
+    report_activity: bool,
+}
+
+impl DataLoaderScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_loader<L>(mut self, loader: &DataLoader<L>) -> Self
+    where
+        L: BatchLoad + 'static,
+    {
+        self.loaders.push(Arc::new(loader.clone()));
+        self
+    }
+
+    /// Adiciona em `extensions.dataloader` da resposta um resumo por loader
+    /// do que a request usou: batches, keys carregadas, hits e misses de
+    /// cache e tempo gasto em `BatchLoad::load`.
+    ///
+    /// O resumo vem das métricas do fork de cada request, então só aparecem
+    /// os loaders passados a `with_loader`; loaders usados fora do escopo
+    /// não são contados.
+    pub fn with_activity_report(mut self) -> Self {
+        self.report_activity = true;
+        self
+    }
+}
+
+impl ExtensionFactory for DataLoaderScope {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(DataLoaderScopeExtension {
+            loaders: self.loaders.clone(),
+            report_activity: self.report_activity,
+            forks: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+struct DataLoaderScopeExtension {
+    loaders: Vec<Arc<dyn ScopedLoader>>,
+    report_activity: bool,
+    forks: Mutex<Vec<Box<dyn ScopedLoader>>>,
+}
+
+#[async_trait]
+impl Extension for DataLoaderScopeExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let mut response = next.run(ctx).await;
+        let forks = std::mem::take(&mut *self.forks.lock().unwrap());
+
+        if self.report_activity {
+            let mut activity = serde_json::Map::new();
+            for fork in &forks {
+                let stats = fork.metrics().get_stats().await;
+                activity.insert(
+                    fork.label().to_string(),
+                    serde_json::json!({
+                        "batchesDispatched": stats.batches_dispatched,
+                        "keysLoaded": stats.keys_processed,
+                        "cacheHits": stats.cache_hits,
+                        "cacheMisses": stats.cache_misses,
+                        "loadTimeMs": stats.load_time.as_secs_f64() * 1000.0,
+                    }),
+                );
+            }
+
+            if let Ok(value) = Value::from_json(serde_json::Value::Object(activity)) {
+                response.extensions.insert("dataloader".to_string(), value);
+            }
+        }
+
+        // Handles clonados por tasks ainda vivas não mantêm dados da request
+        for fork in forks {
+            fork.clear().await;
+        }
+
+        response
+    }
+
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let forks: Vec<_> = self.loaders.iter().map(|loader| loader.fork()).collect();
+        for fork in &forks {
+            request = fork.attach(request);
+        }
+        self.forks.lock().unwrap().extend(forks);
+
+        next.run(ctx, request).await
+    }
+}
+
+// Operações sobre um `DataLoader<L>` sem conhecer `L`
+#[async_trait]
+trait ScopedLoader: Send + Sync {
+    fn fork(&self) -> Box<dyn ScopedLoader>;
+
+    fn attach(&self, request: Request) -> Request;
+
+    fn label(&self) -> &'static str;
+
+    fn metrics(&self) -> Arc<Metrics>;
+
+    async fn clear(&self);
+}
+
+#[async_trait]
+impl<L> ScopedLoader for DataLoader<L>
+where
+    L: BatchLoad + 'static,
+{
+    fn fork(&self) -> Box<dyn ScopedLoader> {
+        Box::new(DataLoader::fork(self))
+    }
+
+    fn attach(&self, request: Request) -> Request {
+        request.data(self.clone())
+    }
+
+    fn label(&self) -> &'static str {
+        std::any::type_name::<L>()
+    }
+
+    fn metrics(&self) -> Arc<Metrics> {
+        DataLoader::metrics(self)
+    }
+
+    async fn clear(&self) {
+        DataLoader::clear(self).await;
+    }
+}
@@ -1,2 +1,3 @@
 // src/integrations/mod.rs
-// Placeholder para futuras integrações
+#[cfg(feature = "graphql")]
+pub mod graphql;
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use sqlx::{Pool, Postgres, FromRow};
 use std::collections::HashMap;
-use super::super::loader::BatchLoad;
+use super::super::loader::{BatchLoad, TryBatchLoad};
 
 #[derive(FromRow, Clone, Debug)]
 pub struct User {
@@ -21,14 +21,14 @@ impl UserLoader {
 }
 
 #[async_trait]
-impl BatchLoad for UserLoader {
+impl TryBatchLoad for UserLoader {
     type Key = i32;
     type Value = User;
-    type Error = String;
+    type Error = sqlx::Error;
 
-    async fn load(&self, keys: &[i32]) -> HashMap<i32, Result<User, String>> {
+    async fn try_load(&self, keys: &[i32]) -> Result<HashMap<i32, User>, sqlx::Error> {
         if keys.is_empty() {
-            return HashMap::new();
+            return Ok(HashMap::new());
         }
 
         // Cria placeholders para a query IN
@@ -46,19 +46,9 @@ impl BatchLoad for UserLoader {
             query = query.bind(key);
         }
 
-        match query.fetch_all(&self.pool).await {
-            Ok(users) => {
-                users.into_iter()
-                    .map(|user| (user.id, Ok(user)))
-                    .collect()
-            }
-            Err(e) => {
-                // Retorna erro para todas as keys
-                keys.iter()
-                    .map(|&key| (key, Err(format!("Database error: {}", e))))
-                    .collect()
-            }
-        }
+        // Um erro de banco falha o batch inteiro
+        let users = query.fetch_all(&self.pool).await?;
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
     }
 }
 
@@ -6,12 +6,22 @@ mod error;
 pub mod integrations;
 mod loader;
 mod metrics;
+mod registry;
+mod tiered;
 
 pub use batcher::{BatchStats, Batcher, Metrics};
-pub use cache::Cache;
+pub use cache::{
+    Cache, CacheLookup, CacheSnapshot, CacheStats, CacheStore, EntryOptions, EvictionPolicy,
+    NoCache, SnapshotEntry,
+};
 pub use error::DataLoaderError;
-pub use loader::{BatchLoad, DataLoader};
+pub use loader::{BatchLoad, DataLoader, ErrorCachePolicy, TryBatchLoad};
 pub use metrics::TelemetryCollector;
+pub use registry::LoaderRegistry;
+pub use tiered::{InMemoryRemoteCache, RemoteCache, TieredCache};
+
+#[cfg(feature = "graphql")]
+pub use integrations::graphql::{AsBatchLoad, AsGraphqlLoader, DataLoaderContext, DataLoaderScope};
 
 // Re-exports comuns
 pub use async_trait::async_trait;
@@ -1,27 +1,234 @@
 // src/loader.rs
-use crate::batcher::Batcher;
-use crate::cache::Cache;
+use crate::batcher::{Batcher, Metrics};
+use crate::cache::{
+    Cache, CacheLookup, CacheSnapshot, CacheStats, CacheStore, EntryOptions, EvictionPolicy,
+    SnapshotEntry,
+};
 use crate::error::DataLoaderError;
 use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
 use std::collections::HashMap;
 
 #[async_trait]
 pub trait BatchLoad: Send + Sync {
     type Key: Send + Sync + Clone + Eq + std::hash::Hash + std::fmt::Debug + 'static;
     type Value: Send + Sync + Clone + 'static;
-    type Error: Send + Sync + From<String> + std::fmt::Display + 'static;
+    type Error: Send + Sync + Into<Box<dyn std::error::Error + Send + Sync>> + 'static;
 
     async fn load(
         &self,
         keys: &[Self::Key],
     ) -> HashMap<Self::Key, Result<Self::Value, Self::Error>>;
+
+    /// Ponto de entrada usado pelo `Batcher`. Um `Err` aqui falha o batch
+    /// inteiro: o mesmo erro é entregue a todos que aguardam as keys.
+    async fn load_batch(
+        &self,
+        keys: &[Self::Key],
+    ) -> Result<HashMap<Self::Key, Result<Self::Value, Self::Error>>, Self::Error> {
+        Ok(self.load(keys).await)
+    }
+
+    /// TTL de cache para um valor específico, sobrepondo o TTL do
+    /// `DataLoader`. Útil para dados voláteis (preços) convivendo com dados
+    /// estáveis (perfis) no mesmo loader.
+    fn cache_ttl(&self, _key: &Self::Key, _value: &Self::Value) -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Tags gravadas junto com o valor em cache, para invalidação em grupo
+    /// com `DataLoader::invalidate_tag` (ex.: `org:42`).
+    fn cache_tags(&self, _key: &Self::Key, _value: &Self::Value) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Alternativa ao `BatchLoad` para loaders que falham o batch inteiro de uma
+/// vez, como uma única query `IN (...)`. Keys ausentes do mapa viram
+/// `DataLoaderError::KeyNotFound`.
+#[async_trait]
+pub trait TryBatchLoad: Send + Sync {
+    type Key: Send + Sync + Clone + Eq + std::hash::Hash + std::fmt::Debug + 'static;
+    type Value: Send + Sync + Clone + 'static;
+    type Error: Send + Sync + Into<Box<dyn std::error::Error + Send + Sync>> + 'static;
+
+    async fn try_load(
+        &self,
+        keys: &[Self::Key],
+    ) -> Result<HashMap<Self::Key, Self::Value>, Self::Error>;
+
+    /// Veja `BatchLoad::cache_ttl`.
+    fn cache_ttl(&self, _key: &Self::Key, _value: &Self::Value) -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Veja `BatchLoad::cache_tags`.
+    fn cache_tags(&self, _key: &Self::Key, _value: &Self::Value) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[async_trait]
+impl<T> BatchLoad for T
+where
+    T: TryBatchLoad,
+{
+    type Key = T::Key;
+    type Value = T::Value;
+    type Error = DataLoaderError;
+
+    async fn load(
+        &self,
+        keys: &[Self::Key],
+    ) -> HashMap<Self::Key, Result<Self::Value, Self::Error>> {
+        match self.load_batch(keys).await {
+            Ok(results) => results,
+            Err(err) => keys
+                .iter()
+                .map(|key| (key.clone(), Err(err.clone())))
+                .collect(),
+        }
+    }
+
+    async fn load_batch(
+        &self,
+        keys: &[Self::Key],
+    ) -> Result<HashMap<Self::Key, Result<Self::Value, Self::Error>>, Self::Error> {
+        match self.try_load(keys).await {
+            Ok(values) => Ok(values
+                .into_iter()
+                .map(|(key, value)| (key, Ok(value)))
+                .collect()),
+            Err(err) => Err(DataLoaderError::batch_error(err)),
+        }
+    }
+
+    fn cache_ttl(&self, key: &Self::Key, value: &Self::Value) -> Option<std::time::Duration> {
+        TryBatchLoad::cache_ttl(self, key, value)
+    }
+
+    fn cache_tags(&self, key: &Self::Key, value: &Self::Value) -> Vec<String> {
+        TryBatchLoad::cache_tags(self, key, value)
+    }
+}
+
+type CachedResult<L> = Result<<L as BatchLoad>::Value, DataLoaderError>;
+type LoaderCache<L> = dyn CacheStore<<L as BatchLoad>::Key, CachedResult<L>>;
+
+/// Define quais erros o `DataLoader` guarda em cache.
+#[derive(Clone, Default)]
+pub enum ErrorCachePolicy {
+    /// Nenhum erro é guardado; a próxima chamada tenta de novo.
+    Never,
+    /// Só keys ausentes (`DataLoaderError::KeyNotFound`) são guardadas.
+    KeyNotFoundOnly,
+    /// Todos os erros são guardados, com o TTL de `DataLoader::with_error_ttl`.
+    /// Sem esse TTL os erros nunca expiram e só saem com uma invalidação.
+    /// Falhas transitórias do próprio dataloader (`Timeout`, `ChannelClosed`)
+    /// nunca são guardadas.
+    #[default]
+    All,
+    /// Guarda os erros para os quais o predicado retorna `true`.
+    Predicate(std::sync::Arc<dyn Fn(&DataLoaderError) -> bool + Send + Sync>),
+}
+
+impl ErrorCachePolicy {
+    pub fn predicate<F>(predicate: F) -> Self
+    where
+        F: Fn(&DataLoaderError) -> bool + Send + Sync + 'static,
+    {
+        ErrorCachePolicy::Predicate(std::sync::Arc::new(predicate))
+    }
+
+    fn should_cache(&self, error: &DataLoaderError) -> bool {
+        match self {
+            ErrorCachePolicy::Never => false,
+            ErrorCachePolicy::KeyNotFoundOnly => matches!(error, DataLoaderError::KeyNotFound),
+            ErrorCachePolicy::All => !matches!(
+                error,
+                DataLoaderError::Timeout | DataLoaderError::ChannelClosed
+            ),
+            ErrorCachePolicy::Predicate(predicate) => predicate(error),
+        }
+    }
+}
+
+impl std::fmt::Debug for ErrorCachePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorCachePolicy::Never => write!(f, "Never"),
+            ErrorCachePolicy::KeyNotFoundOnly => write!(f, "KeyNotFoundOnly"),
+            ErrorCachePolicy::All => write!(f, "All"),
+            ErrorCachePolicy::Predicate(_) => write!(f, "Predicate(..)"),
+        }
+    }
+}
+
+// TTLs aplicados ao gravar no cache; `None` deixa a entrada sem TTL próprio
+#[derive(Debug, Clone, Copy, Default)]
+struct CacheTtls {
+    value: Option<std::time::Duration>,
+    error: Option<std::time::Duration>,
+    missing_key: Option<std::time::Duration>,
+    // Soft TTL dos valores no modo stale-while-revalidate
+    stale_after: Option<std::time::Duration>,
+}
+
+impl CacheTtls {
+    fn entry_options<L: BatchLoad>(
+        &self,
+        loader: &L,
+        key: &L::Key,
+        result: &CachedResult<L>,
+    ) -> EntryOptions {
+        match result {
+            Ok(value) => EntryOptions {
+                ttl: loader.cache_ttl(key, value).or(self.value),
+                stale_after: self.stale_after,
+                tags: loader.cache_tags(key, value),
+            },
+            Err(DataLoaderError::KeyNotFound) => EntryOptions {
+                ttl: self.missing_key.or(self.error),
+                ..EntryOptions::default()
+            },
+            Err(_) => EntryOptions {
+                ttl: self.error,
+                ..EntryOptions::default()
+            },
+        }
+    }
+}
+
+type LoadFuture<L> = Shared<BoxFuture<'static, Result<<L as BatchLoad>::Value, DataLoaderError>>>;
+type InFlight<L> = dashmap::DashMap<<L as BatchLoad>::Key, InFlightLoad<L>>;
+
+// Identifica cada busca para que uma invalidação no meio do caminho impeça
+// o resultado antigo de ser gravado no cache
+static NEXT_LOAD_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+// Tags invalidadas durante a busca. As tags do resultado só são conhecidas
+// no fim, por `BatchLoad::cache_tags`
+type InvalidatedTags = std::sync::Arc<std::sync::Mutex<Vec<String>>>;
+
+struct InFlightLoad<L: BatchLoad> {
+    id: u64,
+    future: LoadFuture<L>,
+    invalidated_tags: InvalidatedTags,
 }
 
 pub struct DataLoader<L: BatchLoad> {
-    batcher: std::sync::Arc<Batcher<L>>,
-    cache: std::sync::Arc<Cache<L::Key, Result<L::Value, DataLoaderError>>>,
-    max_batch_size: usize,
-    delay: std::time::Duration,
+    batcher: Batcher<L>,
+    cache: std::sync::Arc<LoaderCache<L>>,
+    in_flight: std::sync::Arc<InFlight<L>>,
+    // Invalidações pegam a escrita; buscas pegam a leitura para checar o id e
+    // gravar no cache sem uma invalidação no meio
+    invalidation: std::sync::Arc<tokio::sync::RwLock<()>>,
+    ttls: CacheTtls,
+    error_policy: ErrorCachePolicy,
+    load_timeout: Option<std::time::Duration>,
+    sweep_interval: Option<std::time::Duration>,
+    // Compartilhado entre clones, para que só um sweeper rode por cache
+    sweeper: std::sync::Arc<std::sync::Once>,
 }
 
 impl<L> DataLoader<L>
@@ -29,55 +236,482 @@ where
     L: BatchLoad + 'static,
     L::Key: Clone + Eq + std::hash::Hash + std::fmt::Debug,
     L::Value: Clone,
-    L::Error: From<String> + std::fmt::Display,
 {
     pub fn new(loader: L) -> Self {
-        let loader_arc = std::sync::Arc::new(loader);
-        let batcher = std::sync::Arc::new(Batcher::new(std::sync::Arc::clone(&loader_arc)));
-
         Self {
-            batcher,
+            batcher: Batcher::new(std::sync::Arc::new(loader)),
             cache: std::sync::Arc::new(Cache::new()),
-            max_batch_size: 100,
-            delay: std::time::Duration::from_millis(10),
+            in_flight: std::sync::Arc::new(dashmap::DashMap::new()),
+            invalidation: std::sync::Arc::new(tokio::sync::RwLock::new(())),
+            ttls: CacheTtls::default(),
+            error_policy: ErrorCachePolicy::default(),
+            load_timeout: None,
+            sweep_interval: None,
+            sweeper: std::sync::Arc::new(std::sync::Once::new()),
         }
     }
 
     pub fn with_max_batch_size(mut self, size: usize) -> Self {
-        self.max_batch_size = size;
+        self.batcher = self.batcher.with_max_batch_size(size);
+        self
+    }
+
+    pub fn with_max_concurrent_batches(mut self, limit: usize) -> Self {
+        self.batcher = self.batcher.with_max_concurrent_batches(limit);
         self
     }
 
     pub fn with_delay(mut self, delay: std::time::Duration) -> Self {
-        self.delay = delay;
+        self.batcher = self.batcher.with_delay(delay);
+        self
+    }
+
+    /// Troca o cache padrão por outro backend.
+    pub fn with_cache<S>(mut self, store: S) -> Self
+    where
+        S: CacheStore<L::Key, CachedResult<L>> + 'static,
+    {
+        self.cache = std::sync::Arc::new(store);
+        self.sweeper = std::sync::Arc::new(std::sync::Once::new());
+        self
+    }
+
+    /// Substitui o cache por um limitado a `capacity` entradas.
+    pub fn with_cache_capacity(mut self, capacity: usize, policy: EvictionPolicy) -> Self {
+        self.cache = std::sync::Arc::new(Cache::new().with_capacity(capacity, policy));
+        self.sweeper = std::sync::Arc::new(std::sync::Once::new());
+        self
+    }
+
+    /// Remove entradas vencidas do cache a cada `interval`, numa task
+    /// iniciada no primeiro uso do loader, já com o cache definitivo. A task
+    /// termina quando o último handle do loader é descartado. Forks não
+    /// herdam o sweeper.
+    pub fn with_cache_sweeper(mut self, interval: std::time::Duration) -> Self {
+        self.sweep_interval = Some(interval);
+        self.sweeper = std::sync::Arc::new(std::sync::Once::new());
+        self
+    }
+
+    /// Prazo de cada chamada a `BatchLoad::load`; veja `Batcher::with_batch_timeout`.
+    pub fn with_batch_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.batcher = self.batcher.with_batch_timeout(timeout);
+        self
+    }
+
+    /// Prazo que cada chamador espera pelo resultado. Ao estourar, só esse
+    /// chamador recebe `DataLoaderError::Timeout`; a busca continua para os demais.
+    pub fn with_load_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.load_timeout = Some(timeout);
+        self
+    }
+
+    /// TTL dos valores carregados com sucesso. `BatchLoad::cache_ttl` tem
+    /// precedência para keys específicas.
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttls.value = Some(ttl);
+        self
+    }
+
+    /// TTL dos erros guardados em cache. Sem ele, os erros aceitos pela
+    /// `ErrorCachePolicy` ficam em cache até serem invalidados.
+    pub fn with_error_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttls.error = Some(ttl);
+        self
+    }
+
+    /// Ativa o modo stale-while-revalidate: depois de `stale_after` o valor
+    /// em cache ainda é retornado na hora, mas a key entra no próximo batch
+    /// para ser atualizada em background. Só depois do TTL de
+    /// `with_cache_ttl` a chamada volta a esperar pelo loader.
+    pub fn with_stale_while_revalidate(mut self, stale_after: std::time::Duration) -> Self {
+        self.ttls.stale_after = Some(stale_after);
+        self
+    }
+
+    pub fn with_error_cache_policy(mut self, policy: ErrorCachePolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// TTL das entradas negativas, isto é, keys que o loader não retornou.
+    /// Sem essa configuração elas seguem o TTL de erros.
+    pub fn with_missing_key_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttls.missing_key = Some(ttl);
         self
     }
 
     pub async fn load(&self, key: L::Key) -> Result<L::Value, DataLoaderError> {
+        self.start_sweeper();
+
         // Verifica cache primeiro
-        if let Some(cached) = self.cache.get(&key) {
-            return cached;
+        let metrics = self.batcher.metrics();
+        match self.cache.lookup(&key).await {
+            Some(CacheLookup::Fresh(cached)) => {
+                metrics.increment(|m| &m.cache_hits, 1).await;
+                return cached;
+            }
+            Some(CacheLookup::Stale(cached)) => {
+                metrics.increment(|m| &m.cache_hits, 1).await;
+                self.revalidate(key);
+                return cached;
+            }
+            None => metrics.increment(|m| &m.cache_misses, 1).await,
+        }
+
+        // Reaproveita a busca em andamento para a mesma key, se houver
+        let (future, deduplicated) = match self.in_flight.entry(key.clone()) {
+            dashmap::mapref::entry::Entry::Occupied(entry) => (entry.get().future.clone(), true),
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                let future = self.start_fetch(entry, false);
+                (future, false)
+            }
+        };
+
+        if deduplicated {
+            metrics.increment(|m| &m.loads_deduplicated, 1).await;
+        }
+
+        match self.load_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, future).await {
+                Ok(result) => result,
+                Err(_) => {
+                    metrics.increment(|m| &m.timeouts, 1).await;
+                    Err(DataLoaderError::Timeout)
+                }
+            },
+            None => future.await,
+        }
+    }
+
+    /// Como `load`, mas keys ausentes no resultado do loader viram `Ok(None)`.
+    /// Erros reais do batch continuam sendo retornados.
+    pub async fn load_optional(&self, key: L::Key) -> Result<Option<L::Value>, DataLoaderError> {
+        match self.load(key).await {
+            Ok(value) => Ok(Some(value)),
+            Err(DataLoaderError::KeyNotFound) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Carrega várias keys na mesma janela de batch. Keys ausentes no
+    /// resultado do loader são omitidas do mapa; qualquer outro erro é
+    /// retornado.
+    pub async fn load_many(
+        &self,
+        keys: impl IntoIterator<Item = L::Key>,
+    ) -> Result<HashMap<L::Key, L::Value>, DataLoaderError> {
+        let keys: Vec<L::Key> = keys.into_iter().collect();
+        let results = self.try_load_many(keys.iter().cloned()).await;
+
+        let mut values = HashMap::with_capacity(keys.len());
+        for (key, result) in keys.into_iter().zip(results) {
+            match result {
+                Ok(value) => {
+                    values.insert(key, value);
+                }
+                Err(DataLoaderError::KeyNotFound) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Carrega várias keys na mesma janela de batch, retornando um resultado
+    /// por key na mesma ordem da entrada.
+    pub async fn try_load_many(
+        &self,
+        keys: impl IntoIterator<Item = L::Key>,
+    ) -> Vec<Result<L::Value, DataLoaderError>> {
+        futures::future::join_all(keys.into_iter().map(|key| self.load(key))).await
+    }
+
+    /// Novo handle com cache, fila de batches e loads em andamento próprios
+    /// sobre o mesmo loader. Usado para isolar o cache por request; o cache
+    /// do fork é sempre um `Cache` em memória novo, sem limite de capacidade.
+    /// As métricas do fork contam só o seu uso e também acumulam nas deste
+    /// loader.
+    pub fn fork(&self) -> Self {
+        Self {
+            batcher: self.batcher.fork(),
+            cache: std::sync::Arc::new(Cache::new()),
+            in_flight: std::sync::Arc::new(dashmap::DashMap::new()),
+            invalidation: std::sync::Arc::new(tokio::sync::RwLock::new(())),
+            ttls: self.ttls,
+            error_policy: self.error_policy.clone(),
+            load_timeout: self.load_timeout,
+            sweep_interval: None,
+            sweeper: std::sync::Arc::new(std::sync::Once::new()),
+        }
+    }
+
+    pub fn loader(&self) -> &std::sync::Arc<L> {
+        self.batcher.loader()
+    }
+
+    pub fn metrics(&self) -> std::sync::Arc<Metrics> {
+        self.batcher.metrics()
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    // Inicia o sweeper de `with_cache_sweeper` uma única vez por cache
+    fn start_sweeper(&self) {
+        let Some(interval) = self.sweep_interval else {
+            return;
+        };
+
+        self.sweeper.call_once(|| {
+            let cache = std::sync::Arc::downgrade(&self.cache);
+            tokio::spawn(async move {
+                let start = tokio::time::Instant::now() + interval;
+                let mut ticks = tokio::time::interval_at(start, interval);
+                loop {
+                    ticks.tick().await;
+                    match cache.upgrade() {
+                        Some(cache) => {
+                            cache.sweep_expired().await;
+                        }
+                        None => break,
+                    }
+                }
+            });
+        });
+    }
+
+    // Atualiza em background uma entrada stale, a menos que a key já esteja
+    // sendo buscada
+    fn revalidate(&self, key: L::Key) {
+        if let dashmap::mapref::entry::Entry::Vacant(entry) = self.in_flight.entry(key) {
+            // Ninguém aguarda o refresh; a task da busca grava o resultado
+            drop(self.start_fetch(entry, true));
+        }
+    }
+
+    fn start_fetch(
+        &self,
+        entry: dashmap::mapref::entry::VacantEntry<'_, L::Key, InFlightLoad<L>>,
+        revalidating: bool,
+    ) -> LoadFuture<L> {
+        let id = NEXT_LOAD_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let invalidated_tags = InvalidatedTags::default();
+        let future = self
+            .fetch(
+                entry.key().clone(),
+                id,
+                std::sync::Arc::clone(&invalidated_tags),
+                revalidating,
+            )
+            .boxed()
+            .shared();
+
+        // A busca roda numa task própria: o resultado é gravado e a key sai de
+        // `in_flight` mesmo que todos os chamadores desistam no caminho
+        tokio::spawn(future.clone());
+
+        entry.insert(InFlightLoad {
+            id,
+            future: future.clone(),
+            invalidated_tags,
+        });
+        future
+    }
+
+    // Com `revalidating`, um erro não substitui o valor stale que está em cache
+    fn fetch(
+        &self,
+        key: L::Key,
+        id: u64,
+        invalidated_tags: InvalidatedTags,
+        revalidating: bool,
+    ) -> impl std::future::Future<Output = Result<L::Value, DataLoaderError>> + Send + 'static {
+        let batcher = self.batcher.clone();
+        let cache = std::sync::Arc::clone(&self.cache);
+        let in_flight = std::sync::Arc::clone(&self.in_flight);
+        let invalidation = std::sync::Arc::clone(&self.invalidation);
+        let ttls = self.ttls;
+        let error_policy = self.error_policy.clone();
+
+        async move {
+            // Agenda no batcher
+            let result = batcher.schedule(key.clone()).await;
+
+            // Se a key foi invalidada enquanto a busca rodava, o resultado
+            // ainda vai para quem esperava, mas não é gravado no cache
+            let _guard = invalidation.read().await;
+            let current = in_flight.get(&key).is_some_and(|load| load.id == id);
+            let cacheable = match &result {
+                Ok(_) => true,
+                Err(error) => !revalidating && error_policy.should_cache(error),
+            };
+
+            // Cache o resultado antes de liberar a key para novas buscas
+            if current && cacheable {
+                let options = ttls.entry_options(batcher.loader().as_ref(), &key, &result);
+                let invalidated = invalidated_tags
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .any(|tag| options.tags.contains(tag));
+                if !invalidated {
+                    cache
+                        .set_with_options(key.clone(), result.clone(), options)
+                        .await;
+                }
+            }
+            in_flight.remove_if(&key, |_, load| load.id == id);
+
+            result
         }
+    }
+
+    pub async fn clear(&self) {
+        let _guard = self.invalidation.write().await;
+        self.in_flight.clear();
+        self.cache.clear().await;
+    }
+
+    /// Remove a key do cache. Uma busca em andamento para ela não grava
+    /// mais o resultado.
+    pub async fn clear_key(&self, key: &L::Key) {
+        let _guard = self.invalidation.write().await;
+        self.in_flight.remove(key);
+        self.cache.remove(key).await;
+    }
 
-        // Agenda no batcher
-        let result = self.batcher.schedule(key.clone()).await;
+    pub async fn clear_many(&self, keys: impl IntoIterator<Item = L::Key>) {
+        for key in keys {
+            self.clear_key(&key).await;
+        }
+    }
 
-        // Cache o resultado
-        if let Ok(ref value) = result {
-            self.cache.set(key.clone(), Ok(value.clone()));
-        } else if let Err(ref error) = result {
-            self.cache.set(key.clone(), Err(error.clone()));
+    /// Remove as entradas em cache que satisfazem `predicate`, ignorando
+    /// também buscas em andamento para essas keys.
+    pub async fn clear_where<F>(&self, predicate: F)
+    where
+        F: Fn(&L::Key, &CachedResult<L>) -> bool + Send + Sync,
+    {
+        let _guard = self.invalidation.write().await;
+        let removed = self.cache.remove_where(&predicate).await;
+        for key in &removed {
+            self.in_flight.remove(key);
         }
+    }
+
+    pub async fn prime(&self, key: L::Key, value: Result<L::Value, DataLoaderError>) {
+        self.start_sweeper();
+        let options = self
+            .ttls
+            .entry_options(self.batcher.loader().as_ref(), &key, &value);
+        self.cache.set_with_options(key, value, options).await;
+    }
 
-        result
+    /// Como `prime`, acrescentando `tags` às do `BatchLoad::cache_tags`.
+    pub async fn prime_with_tags(
+        &self,
+        key: L::Key,
+        value: Result<L::Value, DataLoaderError>,
+        tags: impl IntoIterator<Item = String>,
+    ) {
+        self.start_sweeper();
+        let mut options = self
+            .ttls
+            .entry_options(self.batcher.loader().as_ref(), &key, &value);
+        options.tags.extend(tags);
+        self.cache.set_with_options(key, value, options).await;
+    }
+
+    /// Remove do cache todas as entradas marcadas com `tag`. Buscas em
+    /// andamento cujo resultado receberia `tag` não são gravadas no cache.
+    pub async fn invalidate_tag(&self, tag: &str) {
+        let _guard = self.invalidation.write().await;
+        let removed = self.cache.invalidate_tag(tag).await;
+        for key in &removed {
+            self.in_flight.remove(key);
+        }
+        for load in self.in_flight.iter() {
+            load.invalidated_tags.lock().unwrap().push(tag.to_string());
+        }
     }
+}
 
-    pub fn clear(&self) {
-        self.cache.clear();
+impl<L> DataLoader<L>
+where
+    L: BatchLoad + 'static,
+    L::Key: serde::Serialize + serde::de::DeserializeOwned,
+    L::Value: serde::Serialize + serde::de::DeserializeOwned,
+{
+    /// Serializa os valores em cache para JSON, por exemplo para aquecer o
+    /// cache no próximo start ou hidratar o cliente numa resposta SSR. Erros
+    /// em cache não são exportados.
+    pub async fn export_json(&self) -> serde_json::Result<String> {
+        let entries = self
+            .cache
+            .export()
+            .await
+            .into_iter()
+            .filter_map(|entry| {
+                let value = entry.value.ok()?;
+                Some(SnapshotEntry {
+                    key: entry.key,
+                    value,
+                    ttl_ms: entry.ttl_ms,
+                    stale_after_ms: entry.stale_after_ms,
+                    tags: entry.tags,
+                })
+            })
+            .collect();
+
+        serde_json::to_string(&CacheSnapshot { entries })
     }
 
-    pub fn prime(&self, key: L::Key, value: Result<L::Value, DataLoaderError>) {
-        self.cache.set(key, value);
+    /// Importa um snapshot de `export_json`, devolvendo quantas entradas
+    /// foram gravadas.
+    pub async fn import_json(&self, json: &str) -> serde_json::Result<usize> {
+        self.start_sweeper();
+        let snapshot: CacheSnapshot<L::Key, L::Value> = serde_json::from_str(json)?;
+        let count = snapshot.entries.len();
+
+        let entries = snapshot
+            .entries
+            .into_iter()
+            .map(|entry| {
+                // Entradas sem TTL no snapshot recebem as opções do loader,
+                // como se tivessem acabado de ser carregadas
+                let value = Ok(entry.value);
+                if entry.ttl_ms.is_some() {
+                    return SnapshotEntry {
+                        key: entry.key,
+                        value,
+                        ttl_ms: entry.ttl_ms,
+                        stale_after_ms: entry.stale_after_ms,
+                        tags: entry.tags,
+                    };
+                }
+
+                let options = self
+                    .ttls
+                    .entry_options(self.loader().as_ref(), &entry.key, &value);
+                let millis = |limit: std::time::Duration| limit.as_millis() as u64;
+                SnapshotEntry {
+                    ttl_ms: options.ttl.map(millis),
+                    stale_after_ms: entry.stale_after_ms.or(options.stale_after.map(millis)),
+                    tags: if entry.tags.is_empty() {
+                        options.tags
+                    } else {
+                        entry.tags
+                    },
+                    key: entry.key,
+                    value,
+                }
+            })
+            .collect();
+        self.cache.import(entries).await;
+
+        Ok(count)
     }
 }
 
@@ -87,10 +721,90 @@ where
 {
     fn clone(&self) -> Self {
         Self {
-            batcher: std::sync::Arc::clone(&self.batcher),
+            batcher: self.batcher.clone(),
             cache: std::sync::Arc::clone(&self.cache),
-            max_batch_size: self.max_batch_size,
-            delay: self.delay,
+            in_flight: std::sync::Arc::clone(&self.in_flight),
+            invalidation: std::sync::Arc::clone(&self.invalidation),
+            ttls: self.ttls,
+            error_policy: self.error_policy.clone(),
+            load_timeout: self.load_timeout,
+            sweep_interval: self.sweep_interval,
+            sweeper: std::sync::Arc::clone(&self.sweeper),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // Preços pares têm TTL curto próprio; os demais seguem o do loader
+    struct PriceLoader;
+
+    #[async_trait]
+    impl BatchLoad for PriceLoader {
+        type Key = u32;
+        type Value = u32;
+        type Error = DataLoaderError;
+
+        async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, DataLoaderError>> {
+            keys.iter().map(|&key| (key, Ok(key))).collect()
+        }
+
+        fn cache_ttl(&self, key: &u32, _value: &u32) -> Option<Duration> {
+            key.is_multiple_of(2).then(|| Duration::from_secs(1))
+        }
+
+        fn cache_tags(&self, key: &u32, _value: &u32) -> Vec<String> {
+            vec![format!("price:{}", key)]
         }
     }
+
+    #[test]
+    fn entry_options_apply_per_kind_ttls() {
+        let ttls = CacheTtls {
+            value: Some(Duration::from_secs(60)),
+            error: Some(Duration::from_secs(5)),
+            missing_key: Some(Duration::from_secs(30)),
+            stale_after: Some(Duration::from_secs(10)),
+        };
+
+        let loader_ttl = ttls.entry_options(&PriceLoader, &2, &Ok(2));
+        assert_eq!(loader_ttl.ttl, Some(Duration::from_secs(1)));
+        assert_eq!(loader_ttl.stale_after, Some(Duration::from_secs(10)));
+        assert_eq!(loader_ttl.tags, vec!["price:2".to_string()]);
+
+        let default_ttl = ttls.entry_options(&PriceLoader, &3, &Ok(3));
+        assert_eq!(default_ttl.ttl, Some(Duration::from_secs(60)));
+
+        let missing = ttls.entry_options(&PriceLoader, &3, &Err(DataLoaderError::KeyNotFound));
+        assert_eq!(missing.ttl, Some(Duration::from_secs(30)));
+        assert_eq!(missing.stale_after, None);
+        assert!(missing.tags.is_empty());
+
+        let failed = ttls.entry_options(&PriceLoader, &3, &Err(DataLoaderError::Timeout));
+        assert_eq!(failed.ttl, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn missing_key_ttl_falls_back_to_error_ttl() {
+        let ttls = CacheTtls {
+            error: Some(Duration::from_secs(5)),
+            ..CacheTtls::default()
+        };
+
+        let missing = ttls.entry_options(&PriceLoader, &3, &Err(DataLoaderError::KeyNotFound));
+        assert_eq!(missing.ttl, Some(Duration::from_secs(5)));
+        assert_eq!(ttls.entry_options(&PriceLoader, &3, &Ok(3)).ttl, None);
+    }
+
+    #[test]
+    fn all_policy_skips_transient_errors() {
+        let policy = ErrorCachePolicy::All;
+        assert!(policy.should_cache(&DataLoaderError::KeyNotFound));
+        assert!(policy.should_cache(&DataLoaderError::batch_error("db down")));
+        assert!(!policy.should_cache(&DataLoaderError::Timeout));
+        assert!(!policy.should_cache(&DataLoaderError::ChannelClosed));
+    }
 }
@@ -28,6 +28,12 @@ struct InternalMetrics {
     errors: u64,
 }
 
+impl Default for TelemetryCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TelemetryCollector {
     pub fn new() -> Self {
         Self {
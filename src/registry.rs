@@ -0,0 +1,213 @@
+// src/registry.rs
+use crate::batcher::{BatchStats, Metrics};
+use crate::loader::{BatchLoad, DataLoader};
+use async_trait::async_trait;
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+type RegistryKey = (TypeId, Option<String>);
+
+/// Guarda os `DataLoader`s de um schema, indexados pelo tipo do `BatchLoad`
+/// e, opcionalmente, por nome para ter mais de um loader do mesmo tipo.
+#[derive(Default)]
+pub struct LoaderRegistry {
+    loaders: HashMap<RegistryKey, RegisteredLoader>,
+}
+
+struct RegisteredLoader {
+    label: String,
+    loader: Box<dyn ErasedLoader>,
+}
+
+impl LoaderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_loader<L>(mut self, loader: DataLoader<L>) -> Self
+    where
+        L: BatchLoad + 'static,
+    {
+        self.register(loader);
+        self
+    }
+
+    pub fn with_named_loader<L>(mut self, name: impl Into<String>, loader: DataLoader<L>) -> Self
+    where
+        L: BatchLoad + 'static,
+    {
+        self.register_named(name, loader);
+        self
+    }
+
+    /// Registra o loader de `L`, substituindo o anterior do mesmo tipo.
+    pub fn register<L>(&mut self, loader: DataLoader<L>)
+    where
+        L: BatchLoad + 'static,
+    {
+        self.insert(None, std::any::type_name::<L>().to_string(), loader);
+    }
+
+    pub fn register_named<L>(&mut self, name: impl Into<String>, loader: DataLoader<L>)
+    where
+        L: BatchLoad + 'static,
+    {
+        let name = name.into();
+        let label = format!("{}[{}]", std::any::type_name::<L>(), name);
+        self.insert(Some(name), label, loader);
+    }
+
+    pub fn get<L>(&self) -> Option<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static,
+    {
+        self.lookup((TypeId::of::<L>(), None))
+    }
+
+    pub fn get_named<L>(&self, name: &str) -> Option<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static,
+    {
+        self.lookup((TypeId::of::<L>(), Some(name.to_string())))
+    }
+
+    pub fn len(&self) -> usize {
+        self.loaders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loaders.is_empty()
+    }
+
+    /// Métricas de cada loader, pelo nome do tipo; loaders nomeados aparecem
+    /// como `Tipo[nome]`.
+    pub async fn stats_by_loader(&self) -> BTreeMap<String, BatchStats> {
+        let mut stats = BTreeMap::new();
+        for entry in self.loaders.values() {
+            let loader_stats = entry.loader.metrics().get_stats().await;
+            stats.insert(entry.label.clone(), loader_stats);
+        }
+        stats
+    }
+
+    /// Soma das métricas de todos os loaders registrados.
+    pub async fn stats(&self) -> BatchStats {
+        let mut total = BatchStats::default();
+        for entry in self.loaders.values() {
+            let stats = entry.loader.metrics().get_stats().await;
+            total.batches_dispatched += stats.batches_dispatched;
+            total.keys_processed += stats.keys_processed;
+            total.loads_deduplicated += stats.loads_deduplicated;
+            total.timeouts += stats.timeouts;
+            total.cache_hits += stats.cache_hits;
+            total.cache_misses += stats.cache_misses;
+            total.load_time += stats.load_time;
+        }
+        total
+    }
+
+    pub async fn clear_all(&self) {
+        for entry in self.loaders.values() {
+            entry.loader.clear().await;
+        }
+    }
+
+    fn insert<L>(&mut self, name: Option<String>, label: String, loader: DataLoader<L>)
+    where
+        L: BatchLoad + 'static,
+    {
+        let entry = RegisteredLoader {
+            label,
+            loader: Box::new(loader),
+        };
+        self.loaders.insert((TypeId::of::<L>(), name), entry);
+    }
+
+    fn lookup<L>(&self, key: RegistryKey) -> Option<&DataLoader<L>>
+    where
+        L: BatchLoad + 'static,
+    {
+        self.loaders
+            .get(&key)?
+            .loader
+            .as_any()
+            .downcast_ref::<DataLoader<L>>()
+    }
+}
+
+// Operações sobre um `DataLoader<L>` sem conhecer `L`
+#[async_trait]
+trait ErasedLoader: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    fn metrics(&self) -> Arc<Metrics>;
+
+    async fn clear(&self);
+}
+
+#[async_trait]
+impl<L> ErasedLoader for DataLoader<L>
+where
+    L: BatchLoad + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> Arc<Metrics> {
+        DataLoader::metrics(self)
+    }
+
+    async fn clear(&self) {
+        DataLoader::clear(self).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DataLoaderError;
+
+    struct Doubler;
+    struct Tripler;
+
+    #[async_trait]
+    impl BatchLoad for Doubler {
+        type Key = u32;
+        type Value = u32;
+        type Error = DataLoaderError;
+
+        async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, DataLoaderError>> {
+            keys.iter().map(|&key| (key, Ok(key * 2))).collect()
+        }
+    }
+
+    #[async_trait]
+    impl BatchLoad for Tripler {
+        type Key = u32;
+        type Value = u32;
+        type Error = DataLoaderError;
+
+        async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, DataLoaderError>> {
+            keys.iter().map(|&key| (key, Ok(key * 3))).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn same_name_on_different_types_keeps_separate_stats() {
+        let registry = LoaderRegistry::new()
+            .with_named_loader("primary", DataLoader::new(Doubler))
+            .with_named_loader("primary", DataLoader::new(Tripler));
+
+        let doubler = registry.get_named::<Doubler>("primary").unwrap();
+        let tripler = registry.get_named::<Tripler>("primary").unwrap();
+        assert_eq!(doubler.load(1).await.unwrap(), 2);
+        assert_eq!(tripler.load(1).await.unwrap(), 3);
+
+        assert_eq!(registry.stats_by_loader().await.len(), 2);
+        assert_eq!(registry.stats().await.batches_dispatched, 2);
+    }
+}
@@ -0,0 +1,416 @@
+// src/tiered.rs
+use crate::cache::{Cache, CacheLookup, CacheStats, CacheStore, EntryOptions, SnapshotEntry};
+use crate::error::DataLoaderError;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    hash::Hash,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Tier compartilhado entre réplicas, no formato de um key-value como o
+/// Redis. Valores chegam já serializados em JSON.
+#[async_trait]
+pub trait RemoteCache: Send + Sync {
+    /// Valor e TTL restante da key (`None` para entradas sem expiração),
+    /// aplicado também quando o valor é copiado para o tier local.
+    async fn get(&self, key: &str) -> Option<(String, Option<Duration>)>;
+
+    async fn set(&self, key: &str, value: String, ttl: Option<Duration>);
+
+    async fn remove(&self, key: &str);
+}
+
+#[async_trait]
+impl<R> RemoteCache for Arc<R>
+where
+    R: RemoteCache + ?Sized,
+{
+    async fn get(&self, key: &str) -> Option<(String, Option<Duration>)> {
+        (**self).get(key).await
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Option<Duration>) {
+        (**self).set(key, value, ttl).await;
+    }
+
+    async fn remove(&self, key: &str) {
+        (**self).remove(key).await;
+    }
+}
+
+/// `RemoteCache` em memória, para testes e desenvolvimento sem um serviço
+/// real.
+#[derive(Default)]
+pub struct InMemoryRemoteCache {
+    store: dashmap::DashMap<String, (String, Option<Instant>)>,
+}
+
+impl InMemoryRemoteCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+}
+
+#[async_trait]
+impl RemoteCache for InMemoryRemoteCache {
+    async fn get(&self, key: &str) -> Option<(String, Option<Duration>)> {
+        let entry = self.store.get(key)?;
+        let (value, expires_at) = entry.value();
+        let now = Instant::now();
+
+        if expires_at.is_some_and(|expires_at| now >= expires_at) {
+            drop(entry);
+            self.store.remove(key);
+            return None;
+        }
+
+        let remaining = expires_at.map(|expires_at| expires_at - now);
+        Some((value.clone(), remaining))
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Option<Duration>) {
+        let expires_at = ttl.map(|ttl| Instant::now() + ttl);
+        self.store.insert(key.to_string(), (value, expires_at));
+    }
+
+    async fn remove(&self, key: &str) {
+        self.store.remove(key);
+    }
+}
+
+// Formato gravado no tier remoto. O soft TTL vira um instante absoluto
+// (ms desde a época Unix) porque cada réplica aquece a entrada em outro momento.
+#[derive(Serialize, Deserialize)]
+struct RemoteEntry<T> {
+    value: T,
+    #[serde(default)]
+    stale_at_ms: Option<u64>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Cache em dois níveis: o `Cache` local da réplica na frente de um
+/// `RemoteCache` compartilhado. Leituras consultam o local e depois o remoto;
+/// gravações vão para os dois. Só valores de sucesso vão para o remoto, erros
+/// ficam no tier local.
+pub struct TieredCache<K, V, R> {
+    local: Cache<K, Result<V, DataLoaderError>>,
+    remote: R,
+    prefix: String,
+}
+
+impl<K, V, R> TieredCache<K, V, R>
+where
+    K: Eq + Hash + Clone + Serialize,
+    V: Clone + Serialize + DeserializeOwned,
+    R: RemoteCache,
+{
+    pub fn new(local: Cache<K, Result<V, DataLoaderError>>, remote: R) -> Self {
+        Self {
+            local,
+            remote,
+            prefix: String::new(),
+        }
+    }
+
+    /// Prefixo das keys no tier remoto, para separar loaders que
+    /// compartilham o mesmo serviço (ex.: `users`).
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn local(&self) -> &Cache<K, Result<V, DataLoaderError>> {
+        &self.local
+    }
+
+    pub fn remote(&self) -> &R {
+        &self.remote
+    }
+
+    fn remote_key(&self, key: &K) -> Option<String> {
+        let key = serde_json::to_string(key).ok()?;
+        if self.prefix.is_empty() {
+            Some(key)
+        } else {
+            Some(format!("{}:{}", self.prefix, key))
+        }
+    }
+
+    async fn remove_remote(&self, key: &K) {
+        if let Some(remote_key) = self.remote_key(key) {
+            self.remote.remove(&remote_key).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<K, V, R> CacheStore<K, Result<V, DataLoaderError>> for TieredCache<K, V, R>
+where
+    K: Eq + Hash + Clone + Serialize + Send + Sync + 'static,
+    V: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+    R: RemoteCache,
+{
+    async fn get(&self, key: &K) -> Option<Result<V, DataLoaderError>> {
+        self.lookup(key).await.map(CacheLookup::into_value)
+    }
+
+    async fn lookup(&self, key: &K) -> Option<CacheLookup<Result<V, DataLoaderError>>> {
+        if let Some(lookup) = self.local.lookup(key) {
+            return Some(lookup);
+        }
+
+        // Miss local: tenta o tier remoto e aquece o local com o resultado,
+        // expirando junto com a entrada remota e mantendo soft TTL e tags
+        let remote_key = self.remote_key(key)?;
+        let (raw, ttl) = self.remote.get(&remote_key).await?;
+        let entry: RemoteEntry<V> = serde_json::from_str(&raw).ok()?;
+
+        let now = unix_millis(SystemTime::now());
+        let stale_after = entry
+            .stale_at_ms
+            .map(|stale_at| Duration::from_millis(stale_at.saturating_sub(now)));
+        let options = EntryOptions {
+            ttl,
+            stale_after,
+            tags: entry.tags,
+        };
+        self.local
+            .set_with_options(key.clone(), Ok(entry.value.clone()), options);
+
+        let value = Ok(entry.value);
+        if stale_after.is_some_and(|stale_after| stale_after.is_zero()) {
+            Some(CacheLookup::Stale(value))
+        } else {
+            Some(CacheLookup::Fresh(value))
+        }
+    }
+
+    async fn set(&self, key: K, value: Result<V, DataLoaderError>) {
+        self.set_with_options(key, value, EntryOptions::default())
+            .await;
+    }
+
+    async fn set_with_ttl(&self, key: K, value: Result<V, DataLoaderError>, ttl: Duration) {
+        let options = EntryOptions {
+            ttl: Some(ttl),
+            ..EntryOptions::default()
+        };
+        self.set_with_options(key, value, options).await;
+    }
+
+    async fn set_with_options(
+        &self,
+        key: K,
+        value: Result<V, DataLoaderError>,
+        options: EntryOptions,
+    ) {
+        if let (Ok(inner), Some(remote_key)) = (&value, self.remote_key(&key)) {
+            let entry = RemoteEntry {
+                value: inner,
+                stale_at_ms: options
+                    .stale_after
+                    .map(|stale_after| unix_millis(SystemTime::now() + stale_after)),
+                tags: options.tags.clone(),
+            };
+            if let Ok(raw) = serde_json::to_string(&entry) {
+                // Sem TTL próprio, a entrada remota segue o TTL do cache local
+                let ttl = options.ttl.or(self.local.default_ttl());
+                self.remote.set(&remote_key, raw, ttl).await;
+            }
+        }
+
+        self.local.set_with_options(key, value, options);
+    }
+
+    async fn remove(&self, key: &K) {
+        self.local.remove(key);
+        self.remove_remote(key).await;
+    }
+
+    async fn remove_where(
+        &self,
+        predicate: &(dyn for<'k, 'v> Fn(&'k K, &'v Result<V, DataLoaderError>) -> bool
+              + Send
+              + Sync),
+    ) -> Vec<K> {
+        let keys = self.local.remove_where(predicate);
+        for key in &keys {
+            self.remove_remote(key).await;
+        }
+        keys
+    }
+
+    async fn invalidate_tag(&self, tag: &str) -> Vec<K> {
+        let keys = self.local.invalidate_tag(tag);
+        for key in &keys {
+            self.remove_remote(key).await;
+        }
+        keys
+    }
+
+    /// Limpa apenas o tier local; o remoto é compartilhado com outras
+    /// réplicas e só perde keys removidas explicitamente.
+    async fn clear(&self) {
+        self.local.clear();
+    }
+
+    async fn sweep_expired(&self) -> usize {
+        self.local.sweep_expired()
+    }
+
+    async fn export(&self) -> Vec<SnapshotEntry<K, Result<V, DataLoaderError>>> {
+        self.local.snapshot()
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.local.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Replica = TieredCache<u32, u32, Arc<InMemoryRemoteCache>>;
+
+    fn replica(remote: &Arc<InMemoryRemoteCache>) -> Replica {
+        TieredCache::new(Cache::new(), Arc::clone(remote)).with_prefix("test")
+    }
+
+    #[tokio::test]
+    async fn in_memory_remote_expires_entries() {
+        let remote = InMemoryRemoteCache::new();
+        remote
+            .set("a", "1".to_string(), Some(Duration::from_millis(20)))
+            .await;
+        remote.set("b", "2".to_string(), None).await;
+
+        let (value, ttl) = remote.get("a").await.unwrap();
+        assert_eq!(value, "1");
+        assert!(ttl.unwrap() <= Duration::from_millis(20));
+        assert_eq!(remote.get("b").await, Some(("2".to_string(), None)));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(remote.get("a").await, None);
+        assert_eq!(remote.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn remote_hit_warms_local_tier() {
+        let remote = Arc::new(InMemoryRemoteCache::new());
+        let a = replica(&remote);
+        let b = replica(&remote);
+
+        CacheStore::set(&a, 7, Ok(70)).await;
+        assert_eq!(remote.len(), 1);
+        assert!(b.local().is_empty());
+
+        assert!(matches!(CacheStore::get(&b, &7).await, Some(Ok(70))));
+        assert_eq!(b.local().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn local_copy_expires_with_remote_entry() {
+        let remote = Arc::new(InMemoryRemoteCache::new());
+        let a = replica(&remote);
+        let b = replica(&remote);
+
+        a.set_with_ttl(7, Ok(70), Duration::from_millis(50)).await;
+        assert!(CacheStore::get(&b, &7).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(CacheStore::get(&b, &7).await.is_none());
+        assert!(b.local().is_empty());
+    }
+
+    #[tokio::test]
+    async fn errors_stay_in_local_tier() {
+        let remote = Arc::new(InMemoryRemoteCache::new());
+        let a = replica(&remote);
+
+        CacheStore::set(&a, 1, Err(DataLoaderError::KeyNotFound)).await;
+        assert_eq!(a.local().len(), 1);
+        assert!(remote.is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalidation_reaches_remote_but_clear_does_not() {
+        let remote = Arc::new(InMemoryRemoteCache::new());
+        let a = replica(&remote);
+        let tagged = EntryOptions {
+            tags: vec!["org:1".to_string()],
+            ..EntryOptions::default()
+        };
+
+        a.set_with_options(1, Ok(10), tagged).await;
+        CacheStore::set(&a, 2, Ok(20)).await;
+        CacheStore::set(&a, 3, Ok(30)).await;
+        assert_eq!(remote.len(), 3);
+
+        assert_eq!(CacheStore::invalidate_tag(&a, "org:1").await, vec![1]);
+        CacheStore::remove(&a, &2).await;
+        assert_eq!(remote.len(), 1);
+
+        CacheStore::clear(&a).await;
+        assert!(a.local().is_empty());
+        assert_eq!(remote.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn warmed_replica_keeps_tags_and_stale_after() {
+        let remote = Arc::new(InMemoryRemoteCache::new());
+        let a = replica(&remote);
+        let b = replica(&remote);
+        let options = EntryOptions {
+            stale_after: Some(Duration::from_millis(20)),
+            tags: vec!["org:1".to_string()],
+            ..EntryOptions::default()
+        };
+
+        a.set_with_options(1, Ok(10), options).await;
+        assert!(matches!(
+            CacheStore::lookup(&b, &1).await,
+            Some(CacheLookup::Fresh(Ok(10)))
+        ));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(matches!(
+            CacheStore::lookup(&b, &1).await,
+            Some(CacheLookup::Stale(Ok(10)))
+        ));
+
+        assert_eq!(CacheStore::invalidate_tag(&b, "org:1").await, vec![1]);
+        assert!(b.local().is_empty());
+        assert!(remote.is_empty());
+    }
+
+    #[tokio::test]
+    async fn remote_entry_follows_local_default_ttl() {
+        let remote = Arc::new(InMemoryRemoteCache::new());
+        let a: Replica = TieredCache::new(
+            Cache::new().with_ttl(Duration::from_millis(50)),
+            Arc::clone(&remote),
+        );
+
+        CacheStore::set(&a, 1, Ok(10)).await;
+        let (_, ttl) = remote.get("1").await.unwrap();
+        assert!(ttl.is_some_and(|ttl| ttl <= Duration::from_millis(50)));
+    }
+}
@@ -0,0 +1,150 @@
+use async_graphql_dataloader::{BatchLoad, DataLoader, DataLoaderError};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+struct SlowLoader;
+
+#[async_trait::async_trait]
+impl BatchLoad for SlowLoader {
+    type Key = u32;
+    type Value = u32;
+    type Error = String;
+
+    async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, String>> {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        keys.iter().map(|&key| (key, Ok(key * 10))).collect()
+    }
+}
+
+#[tokio::test]
+async fn cancelled_caller_does_not_fail_other_waiters() {
+    let loader = DataLoader::new(SlowLoader).with_delay(Duration::from_millis(10));
+
+    // O primeiro chamador abre a janela e é cancelado com o batch em andamento
+    let first = tokio::spawn({
+        let loader = loader.clone();
+        async move { loader.load(1).await }
+    });
+    tokio::time::sleep(Duration::from_millis(1)).await;
+
+    let second = tokio::spawn({
+        let loader = loader.clone();
+        async move { loader.load(2).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    first.abort();
+
+    let result = tokio::time::timeout(Duration::from_secs(1), second)
+        .await
+        .expect("second caller hung")
+        .unwrap();
+    assert_eq!(result.unwrap(), 20);
+
+    let stats = loader.metrics().get_stats().await;
+    assert_eq!(stats.batches_dispatched, 1);
+    assert_eq!(stats.keys_processed, 2);
+}
+
+#[tokio::test]
+async fn abandoned_load_is_still_cached() {
+    let loader = DataLoader::new(SlowLoader)
+        .with_delay(Duration::from_millis(1))
+        .with_load_timeout(Duration::from_millis(10));
+
+    // O único chamador desiste antes do batch terminar
+    assert!(loader.load(1).await.is_err());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(loader.cache_stats().entries, 1);
+    assert_eq!(loader.load(1).await.unwrap(), 10);
+    assert_eq!(loader.metrics().get_stats().await.batches_dispatched, 1);
+}
+
+// Registra o tamanho de cada batch e quantos rodaram ao mesmo tempo
+#[derive(Default)]
+struct RecordingLoader {
+    batch_sizes: Mutex<Vec<usize>>,
+    running: AtomicUsize,
+    max_running: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl BatchLoad for RecordingLoader {
+    type Key = u32;
+    type Value = u32;
+    type Error = String;
+
+    async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, String>> {
+        self.batch_sizes.lock().unwrap().push(keys.len());
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+
+        keys.iter().map(|&key| (key, Ok(key * 10))).collect()
+    }
+}
+
+#[tokio::test]
+async fn oversized_window_is_split_into_chunks() {
+    let loader = DataLoader::new(RecordingLoader::default()).with_max_batch_size(3);
+
+    let results = loader.try_load_many(0..10).await;
+    let values: Vec<u32> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(values, (0..10).map(|key| key * 10).collect::<Vec<_>>());
+
+    let recorder = loader.loader();
+    assert_eq!(*recorder.batch_sizes.lock().unwrap(), vec![3, 3, 3, 1]);
+    assert_eq!(recorder.max_running.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn chunks_run_up_to_max_concurrent_batches() {
+    let loader = DataLoader::new(RecordingLoader::default())
+        .with_max_batch_size(3)
+        .with_max_concurrent_batches(2);
+
+    let results = loader.try_load_many(0..10).await;
+    assert!(results.iter().all(Result::is_ok));
+
+    let recorder = loader.loader();
+    assert_eq!(recorder.batch_sizes.lock().unwrap().len(), 4);
+    assert_eq!(recorder.max_running.load(Ordering::SeqCst), 2);
+}
+
+struct HungLoader;
+
+#[async_trait::async_trait]
+impl BatchLoad for HungLoader {
+    type Key = u32;
+    type Value = u32;
+    type Error = String;
+
+    async fn load(&self, _keys: &[u32]) -> HashMap<u32, Result<u32, String>> {
+        futures::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn hung_batch_times_out_for_every_waiter() {
+    let loader = DataLoader::new(HungLoader).with_batch_timeout(Duration::from_millis(20));
+
+    let results = tokio::time::timeout(Duration::from_secs(1), loader.try_load_many([1, 2, 3]))
+        .await
+        .expect("waiters hung");
+    assert_eq!(results.len(), 3);
+    assert!(results
+        .iter()
+        .all(|result| matches!(result, Err(DataLoaderError::Timeout))));
+
+    let stats = loader.metrics().get_stats().await;
+    assert_eq!(stats.batches_dispatched, 1);
+    assert_eq!(stats.timeouts, 1);
+}
@@ -0,0 +1,245 @@
+#![cfg(feature = "graphql")]
+
+use async_graphql::dataloader::Loader;
+use async_graphql::{EmptyMutation, EmptySubscription, ErrorExtensions, Object, Schema};
+use async_graphql_dataloader::{
+    AsBatchLoad, AsGraphqlLoader, BatchLoad, DataLoader, DataLoaderContext, DataLoaderScope,
+    LoaderRegistry,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+// Ids negativos não existem; o id zero faz o banco falhar
+struct UserLoader;
+
+#[async_trait::async_trait]
+impl BatchLoad for UserLoader {
+    type Key = i32;
+    type Value = String;
+    type Error = String;
+
+    async fn load(&self, keys: &[i32]) -> HashMap<i32, Result<String, String>> {
+        keys.iter()
+            .filter(|&&id| id >= 0)
+            .map(|&id| match id {
+                0 => (id, Err("db down".to_string())),
+                _ => (id, Ok(format!("User {}", id))),
+            })
+            .collect()
+    }
+}
+
+// Mesmo loader escrito para o dataloader do async-graphql
+struct LegacyUserLoader;
+
+#[async_trait::async_trait]
+impl Loader<i32> for LegacyUserLoader {
+    type Value = String;
+    type Error = String;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, String>, String> {
+        if keys.contains(&0) {
+            return Err("db down".to_string());
+        }
+        Ok(keys
+            .iter()
+            .map(|&id| (id, format!("User {}", id)))
+            .collect())
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn user(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        id: i32,
+    ) -> async_graphql::Result<String> {
+        ctx.data_loader::<UserLoader>()?
+            .load(id)
+            .await
+            .map_err(|err| err.extend())
+    }
+
+    async fn user_without_code(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        id: i32,
+    ) -> async_graphql::Result<String> {
+        Ok(ctx.data_loader::<UserLoader>()?.load(id).await?)
+    }
+}
+
+type TestSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+async fn execute(schema: &TestSchema, query: &str) -> serde_json::Value {
+    serde_json::to_value(schema.execute(query).await).unwrap()
+}
+
+#[tokio::test]
+async fn data_loader_comes_from_schema_data() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(DataLoader::new(UserLoader))
+        .finish();
+
+    let response = execute(&schema, "{ user(id: 1) }").await;
+    assert_eq!(response["data"]["user"], "User 1");
+}
+
+#[tokio::test]
+async fn data_loader_falls_back_to_registry() {
+    let registry = LoaderRegistry::new().with_loader(DataLoader::new(UserLoader));
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(registry)
+        .finish();
+
+    let response = execute(&schema, "{ user(id: 2) }").await;
+    assert_eq!(response["data"]["user"], "User 2");
+}
+
+#[tokio::test]
+async fn missing_loader_is_reported() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription).finish();
+
+    let response = execute(&schema, "{ user(id: 1) }").await;
+    let message = response["errors"][0]["message"].as_str().unwrap();
+    assert!(
+        message.contains("UserLoader> is not registered"),
+        "{}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn extend_sets_error_code() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(DataLoader::new(UserLoader))
+        .finish();
+
+    let response = execute(&schema, "{ user(id: -1) }").await;
+    assert_eq!(response["errors"][0]["extensions"]["code"], "KEY_NOT_FOUND");
+
+    // Com `?` o erro passa pelo `Display` e fica sem `code`
+    let response = execute(&schema, "{ userWithoutCode(id: -1) }").await;
+    assert!(response["errors"][0]["message"].is_string());
+    assert!(response["errors"][0].get("extensions").is_none());
+}
+
+#[tokio::test]
+async fn as_batch_load_runs_graphql_loader() {
+    let loader = DataLoader::new(AsBatchLoad::new(LegacyUserLoader));
+
+    let users = loader.load_many(vec![1, 2]).await.unwrap();
+    assert_eq!(users[&1], "User 1");
+    assert_eq!(users[&2], "User 2");
+}
+
+#[tokio::test]
+async fn as_batch_load_fails_every_waiter_on_loader_error() {
+    let loader = DataLoader::new(AsBatchLoad::new(LegacyUserLoader));
+
+    let (failed, other) = tokio::join!(loader.load(0), loader.load(3));
+    for result in [failed, other] {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), "BATCH_ERROR");
+        assert!(error.to_string().contains("db down"));
+    }
+}
+
+#[tokio::test]
+async fn as_graphql_loader_runs_batch_load() {
+    let loader =
+        async_graphql::dataloader::DataLoader::new(AsGraphqlLoader::new(UserLoader), tokio::spawn);
+
+    let users = loader.load_many(vec![1, 2, -1]).await.unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[&1], "User 1");
+    assert_eq!(loader.load_one(-1).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn as_graphql_loader_fails_whole_batch_on_key_error() {
+    let loader =
+        async_graphql::dataloader::DataLoader::new(AsGraphqlLoader::new(UserLoader), tokio::spawn);
+
+    let (failed, other) = tokio::join!(loader.load_one(0), loader.load_one(3));
+    for result in [failed, other] {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), "BATCH_ERROR");
+        assert!(error.to_string().contains("db down"));
+    }
+}
+
+// Guarda o loader visto pelo resolver para inspecioná-lo depois da request
+#[derive(Default)]
+struct SeenLoader(Mutex<Option<DataLoader<UserLoader>>>);
+
+struct ScopedQuery;
+
+#[Object]
+impl ScopedQuery {
+    async fn user(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        id: i32,
+    ) -> async_graphql::Result<String> {
+        let loader = ctx.data_loader::<UserLoader>()?;
+        *ctx.data_unchecked::<Arc<SeenLoader>>().0.lock().unwrap() = Some(loader.clone());
+        loader.load(id).await.map_err(|err| err.extend())
+    }
+}
+
+#[tokio::test]
+async fn scope_gives_each_request_its_own_cache() {
+    let shared = DataLoader::new(UserLoader);
+    let seen_loader = Arc::new(SeenLoader::default());
+    let schema = Schema::build(ScopedQuery, EmptyMutation, EmptySubscription)
+        .data(shared.clone())
+        .data(Arc::clone(&seen_loader))
+        .extension(DataLoaderScope::new().with_loader(&shared))
+        .finish();
+
+    for _ in 0..2 {
+        let response = serde_json::to_value(schema.execute("{ user(id: 1) }").await).unwrap();
+        assert_eq!(response["data"]["user"], "User 1");
+
+        // O fork da request carregou a key sozinho e foi limpo no fim
+        let seen = seen_loader.0.lock().unwrap().take().unwrap();
+        let stats = seen.metrics().get_stats().await;
+        assert_eq!((stats.batches_dispatched, stats.cache_hits), (1, 0));
+        assert_eq!(seen.cache_stats().entries, 0);
+    }
+
+    // As duas requests contam nas métricas do loader compartilhado, cujo
+    // cache nunca foi usado
+    assert_eq!(shared.metrics().get_stats().await.batches_dispatched, 2);
+    assert_eq!(shared.cache_stats().entries, 0);
+}
+
+#[tokio::test]
+async fn activity_report_lists_each_scoped_loader() {
+    let shared = DataLoader::new(UserLoader);
+    let schema = Schema::build(ScopedQuery, EmptyMutation, EmptySubscription)
+        .data(shared.clone())
+        .data(Arc::new(SeenLoader::default()))
+        .extension(
+            DataLoaderScope::new()
+                .with_loader(&shared)
+                .with_activity_report(),
+        )
+        .finish();
+
+    let response = schema.execute("{ a: user(id: 1) b: user(id: 2) }").await;
+    let report = serde_json::to_value(&response.extensions["dataloader"]).unwrap();
+    let activity = &report[std::any::type_name::<UserLoader>()];
+
+    assert_eq!(activity["batchesDispatched"], 1);
+    assert_eq!(activity["keysLoaded"], 2);
+    assert_eq!(activity["cacheHits"], 0);
+    assert_eq!(activity["cacheMisses"], 2);
+    assert!(activity["loadTimeMs"].as_f64().is_some());
+}
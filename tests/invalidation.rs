@@ -0,0 +1,78 @@
+use async_graphql_dataloader::{BatchLoad, DataLoader};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+// A primeira chamada é lenta; cada chamada devolve a sua versão do valor.
+// Todas as keys pertencem à organização 1
+#[derive(Default)]
+struct VersionedLoader {
+    calls: AtomicU32,
+}
+
+#[async_trait::async_trait]
+impl BatchLoad for VersionedLoader {
+    type Key = u32;
+    type Value = u32;
+    type Error = String;
+
+    async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, String>> {
+        let version = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if version == 1 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        keys.iter()
+            .map(|&key| (key, Ok(key * 1000 + version)))
+            .collect()
+    }
+
+    fn cache_tags(&self, _key: &u32, _value: &u32) -> Vec<String> {
+        vec!["org:1".to_string()]
+    }
+}
+
+#[tokio::test]
+async fn invalidated_fetch_does_not_overwrite_newer_value() {
+    let loader = DataLoader::new(VersionedLoader::default()).with_delay(Duration::from_millis(1));
+
+    let stale = tokio::spawn({
+        let loader = loader.clone();
+        async move { loader.load(1).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // Invalida com a primeira busca em andamento e busca de novo
+    loader.clear_key(&1).await;
+    assert_eq!(loader.load(1).await.unwrap(), 1002);
+
+    // Quem esperava a busca antiga recebe o valor antigo, mas o cache não muda
+    assert_eq!(stale.await.unwrap().unwrap(), 1001);
+    assert_eq!(loader.cache_stats().entries, 1);
+    assert_eq!(loader.load(1).await.unwrap(), 1002);
+    assert_eq!(loader.metrics().get_stats().await.batches_dispatched, 2);
+}
+
+#[tokio::test]
+async fn tag_invalidated_during_fetch_is_not_cached() {
+    let loader = DataLoader::new(VersionedLoader::default()).with_delay(Duration::from_millis(1));
+    loader
+        .prime_with_tags(2, Ok(2000), vec!["featured".to_string()])
+        .await;
+
+    let stale = tokio::spawn({
+        let loader = loader.clone();
+        async move { loader.load(1).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // A key 1 só recebe `org:1` quando a busca termina
+    loader.invalidate_tag("org:1").await;
+    assert_eq!(stale.await.unwrap().unwrap(), 1001);
+    assert_eq!(loader.cache_stats().entries, 0);
+
+    assert_eq!(loader.load(1).await.unwrap(), 1002);
+    assert_eq!(loader.load(1).await.unwrap(), 1002);
+    assert_eq!(loader.metrics().get_stats().await.batches_dispatched, 2);
+}
@@ -0,0 +1,98 @@
+use async_graphql_dataloader::{BatchLoad, DataLoader};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+// Produtos com id zero não existem; cada produto leva a tag `product:{id}`
+#[derive(Default)]
+struct ProductLoader {
+    calls: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl BatchLoad for ProductLoader {
+    type Key = u32;
+    type Value = String;
+    type Error = String;
+
+    async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<String, String>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        keys.iter()
+            .filter(|&&key| key != 0)
+            .map(|&key| (key, Ok(format!("product-{}", key))))
+            .collect()
+    }
+
+    fn cache_tags(&self, key: &u32, _value: &String) -> Vec<String> {
+        vec![format!("product:{}", key)]
+    }
+}
+
+fn products(ttl: Duration) -> DataLoader<ProductLoader> {
+    DataLoader::new(ProductLoader::default())
+        .with_delay(Duration::from_millis(1))
+        .with_cache_ttl(ttl)
+        .with_missing_key_ttl(Duration::from_secs(10))
+}
+
+fn calls(loader: &DataLoader<ProductLoader>) -> usize {
+    loader.loader().calls.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn export_skips_errors_and_import_keeps_remaining_ttl() {
+    let source = products(Duration::from_millis(100));
+    source.load(1).await.unwrap();
+    assert!(source.load(0).await.is_err());
+    assert_eq!(source.cache_stats().entries, 2);
+    tokio::time::sleep(Duration::from_millis(40)).await;
+
+    let target = products(Duration::from_secs(10));
+    assert_eq!(
+        target
+            .import_json(&source.export_json().await.unwrap())
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(target.load(1).await.unwrap(), "product-1");
+    assert_eq!(calls(&target), 0);
+
+    // Vale o tempo restante do snapshot, não o TTL do loader de destino
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    target.load(1).await.unwrap();
+    assert_eq!(calls(&target), 1);
+}
+
+#[tokio::test]
+async fn imported_tags_can_be_invalidated() {
+    let source = products(Duration::from_secs(10));
+    source.load_many(vec![1, 2]).await.unwrap();
+
+    let target = products(Duration::from_secs(10));
+    target
+        .import_json(&source.export_json().await.unwrap())
+        .await
+        .unwrap();
+    target.invalidate_tag("product:1").await;
+
+    target.load_many(vec![1, 2]).await.unwrap();
+    assert_eq!(calls(&target), 1);
+    assert_eq!(target.cache_stats().entries, 2);
+}
+
+#[tokio::test]
+async fn import_without_ttl_uses_loader_options() {
+    let target = products(Duration::from_millis(30));
+    let json = r#"{"entries":[{"key":1,"value":"product-1","ttl_ms":null,"stale_after_ms":null,"tags":[]}]}"#;
+    assert_eq!(target.import_json(json).await.unwrap(), 1);
+
+    let exported = target.export_json().await.unwrap();
+    assert!(exported.contains(r#""tags":["product:1"]"#));
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    target.load(1).await.unwrap();
+    assert_eq!(calls(&target), 1);
+}
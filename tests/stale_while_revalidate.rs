@@ -0,0 +1,74 @@
+use async_graphql_dataloader::{BatchLoad, DataLoader};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+// Cada chamada devolve uma nova versão do valor, ou falha com `failing`
+#[derive(Default)]
+struct CatalogLoader {
+    calls: AtomicU32,
+    failing: AtomicBool,
+}
+
+#[async_trait::async_trait]
+impl BatchLoad for CatalogLoader {
+    type Key = u32;
+    type Value = u32;
+    type Error = String;
+
+    async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, String>> {
+        let version = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let failing = self.failing.load(Ordering::SeqCst);
+        keys.iter()
+            .map(|&key| {
+                let result = if failing {
+                    Err("db down".to_string())
+                } else {
+                    Ok(key * 1000 + version)
+                };
+                (key, result)
+            })
+            .collect()
+    }
+}
+
+fn catalog() -> DataLoader<CatalogLoader> {
+    DataLoader::new(CatalogLoader::default())
+        .with_delay(Duration::from_millis(5))
+        .with_cache_ttl(Duration::from_secs(10))
+        .with_stale_while_revalidate(Duration::from_millis(20))
+}
+
+#[tokio::test]
+async fn stale_value_is_served_and_refreshed_in_background() {
+    let loader = catalog();
+    assert_eq!(loader.load(1).await.unwrap(), 1001);
+    tokio::time::sleep(Duration::from_millis(40)).await;
+
+    // Passou do soft TTL: volta na hora, sem esperar a janela do batch
+    let started = Instant::now();
+    assert_eq!(loader.load(1).await.unwrap(), 1001);
+    assert!(started.elapsed() < Duration::from_millis(5));
+    assert_eq!(loader.metrics().get_stats().await.cache_hits, 1);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(loader.load(1).await.unwrap(), 1002);
+    assert_eq!(loader.metrics().get_stats().await.batches_dispatched, 2);
+}
+
+#[tokio::test]
+async fn failed_refresh_keeps_stale_value() {
+    let loader = catalog();
+    assert_eq!(loader.load(1).await.unwrap(), 1001);
+    tokio::time::sleep(Duration::from_millis(40)).await;
+
+    loader.loader().failing.store(true, Ordering::SeqCst);
+    assert_eq!(loader.load(1).await.unwrap(), 1001);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(loader.metrics().get_stats().await.batches_dispatched, 2);
+    assert_eq!(loader.load(1).await.unwrap(), 1001);
+    assert_eq!(loader.cache_stats().entries, 1);
+}
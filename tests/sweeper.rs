@@ -0,0 +1,35 @@
+use async_graphql_dataloader::{BatchLoad, DataLoader, EvictionPolicy};
+use std::{collections::HashMap, time::Duration};
+
+struct EchoLoader;
+
+#[async_trait::async_trait]
+impl BatchLoad for EchoLoader {
+    type Key = u32;
+    type Value = u32;
+    type Error = String;
+
+    async fn load(&self, keys: &[u32]) -> HashMap<u32, Result<u32, String>> {
+        keys.iter().map(|&key| (key, Ok(key))).collect()
+    }
+}
+
+#[test]
+fn sweeper_follows_cache_set_after_it() {
+    // Montado fora do runtime e com o cache trocado depois do sweeper
+    let loader = DataLoader::new(EchoLoader)
+        .with_cache_ttl(Duration::from_millis(20))
+        .with_cache_sweeper(Duration::from_millis(10))
+        .with_cache_capacity(10, EvictionPolicy::Lru);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        loader.load(1).await.unwrap();
+        assert_eq!(loader.cache_stats().entries, 1);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let stats = loader.cache_stats();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.swept, 1);
+    });
+}